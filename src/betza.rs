@@ -0,0 +1,330 @@
+use crate::piece::{Movement, Piece};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Offsets of the single letter atoms
+const ATOMS: [(char, i64, i64); 9] = [
+    ('W', 1, 0),
+    ('F', 1, 1),
+    ('D', 2, 0),
+    ('N', 2, 1),
+    ('A', 2, 2),
+    ('H', 3, 0),
+    ('C', 3, 1),
+    ('Z', 3, 2),
+    ('G', 3, 3),
+];
+
+/// Atoms of the letters that stand for several atoms, and whether they ride
+const SHORTHANDS: [(char, &str, bool); 4] = [
+    ('K', "WF", false),
+    ('R', "W", true),
+    ('B', "F", true),
+    ('Q', "WF", true),
+];
+
+/// Parse a piece from its Betza movement notation
+///
+/// Each atom is an uppercase letter (`W`, `F`, `D`, `N`, `A`, `H`, `C`, `Z`, `G`) or an
+/// arbitrary leap written `(dx,dy)`, and moves once in all of its directions. Writing the
+/// atom twice makes it a rider, as in `NN` for the nightrider, and a number after the atom
+/// limits the range, `0` meaning no limit. `K`, `R`, `B` and `Q` are shorthands for the
+/// usual pieces.
+///
+/// Attacks must be symmetric, so the only modifiers accepted are `v` (vertical moves only),
+/// `s` (sideways moves only), `c` (capture only) and `m` (move only, so not an attack).
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::betza::parse_betza;
+/// use andaluz_core::piece::Piece;
+///
+/// let queen = parse_betza("WWFF").unwrap();
+/// assert_eq!(queen.get_movements(), Piece::queen().get_movements());
+///
+/// let camel = parse_betza("C").unwrap();
+/// assert_eq!(camel.get_movements().len(), 8);
+///
+/// assert!(parse_betza("fW").is_err());
+/// assert!(parse_betza("X").is_err());
+/// ```
+pub fn parse_betza(notation: &str) -> Result<Piece, String> {
+    let mut chars = notation.char_indices().peekable();
+    let mut movements = vec![];
+    let mut modifiers = String::new();
+
+    if notation.trim().is_empty() {
+        return Err("Empty Betza notation".to_string());
+    }
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            'a'..='z' => modifiers.push(c),
+            'A'..='Z' | '(' => {
+                let atom = parse_atom(c, position, &mut chars)?;
+
+                // A repeated atom rides, a number sets the range
+                let range = if parse_atom_repeat(&atom, &mut chars)? {
+                    None
+                } else {
+                    match parse_number(&mut chars) {
+                        Some(0) => None,
+                        Some(n) => Some(n),
+                        None if atom.rides => None,
+                        None => Some(1),
+                    }
+                };
+
+                movements.extend(apply_modifiers(&modifiers, &atom, range)?);
+                modifiers.clear();
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected character '{}' at position {} of '{}'",
+                    c, position, notation
+                ))
+            }
+        }
+    }
+
+    if !modifiers.is_empty() {
+        return Err(format!("Modifiers '{}' without an atom", modifiers));
+    }
+
+    if movements.is_empty() {
+        return Err(format!("'{}' doesn't attack any cell", notation));
+    }
+
+    Ok(Piece::new(notation, &movements))
+}
+
+/// Leaps of an atom, as written in the notation
+#[derive(Debug)]
+struct Atom {
+    text: String,
+    leaps: Vec<(i64, i64)>,
+    rides: bool,
+}
+
+fn parse_atom(c: char, position: usize, chars: &mut Peekable<CharIndices>) -> Result<Atom, String> {
+    if c == '(' {
+        let mut text = String::new();
+        loop {
+            match chars.next() {
+                Some((_, ')')) => break,
+                Some((_, d)) => text.push(d),
+                None => return Err(format!("Unclosed '(' at position {}", position)),
+            }
+        }
+
+        let offsets = text
+            .split(',')
+            .map(|v| v.trim().parse::<i64>())
+            .collect::<Result<Vec<i64>, _>>()
+            .map_err(|_| format!("Invalid leap '({})'", text))?;
+
+        return match offsets[..] {
+            [dx, dy] if dx != 0 || dy != 0 => Ok(Atom {
+                text: format!("({})", text),
+                leaps: vec![(dx, dy)],
+                rides: false,
+            }),
+            _ => Err(format!("Invalid leap '({})'", text)),
+        };
+    }
+
+    if let Some((_, dx, dy)) = ATOMS.iter().find(|(a, _, _)| *a == c) {
+        return Ok(Atom {
+            text: c.to_string(),
+            leaps: vec![(*dx, *dy)],
+            rides: false,
+        });
+    }
+
+    if let Some((_, atoms, rides)) = SHORTHANDS.iter().find(|(s, _, _)| *s == c) {
+        let leaps = atoms
+            .chars()
+            .filter_map(|a| ATOMS.iter().find(|(b, _, _)| *b == a))
+            .map(|(_, dx, dy)| (*dx, *dy))
+            .collect();
+        return Ok(Atom {
+            text: c.to_string(),
+            leaps,
+            rides: *rides,
+        });
+    }
+
+    Err(format!("Unknown atom '{}' at position {}", c, position))
+}
+
+/// Consume a repetition of `atom`, if it comes next
+fn parse_atom_repeat(atom: &Atom, chars: &mut Peekable<CharIndices>) -> Result<bool, String> {
+    let (position, c) = match chars.peek() {
+        Some((p, c)) if *c == '(' || atom.text.starts_with(*c) => (*p, *c),
+        _ => return Ok(false),
+    };
+
+    let mut ahead = chars.clone();
+    ahead.next();
+    let next = parse_atom(c, position, &mut ahead)?;
+
+    if next.text != atom.text {
+        return Ok(false);
+    }
+
+    if atom.rides {
+        return Err(format!(
+            "'{}' already rides and can't be repeated",
+            atom.text
+        ));
+    }
+
+    *chars = ahead;
+    Ok(true)
+}
+
+fn parse_number(chars: &mut Peekable<CharIndices>) -> Option<usize> {
+    let mut digits = String::new();
+    while let Some((_, d)) = chars.peek() {
+        if !d.is_ascii_digit() {
+            break;
+        }
+        digits.push(*d);
+        chars.next();
+    }
+
+    digits.parse().ok()
+}
+
+fn apply_modifiers(
+    modifiers: &str,
+    atom: &Atom,
+    range: Option<usize>,
+) -> Result<Vec<Movement>, String> {
+    let mut vertical = false;
+    let mut sideways = false;
+    let mut moves = false;
+    let mut captures = false;
+
+    for m in modifiers.chars() {
+        match m {
+            'v' => vertical = true,
+            's' => sideways = true,
+            'm' => moves = true,
+            'c' => captures = true,
+            'f' | 'b' | 'l' | 'r' => {
+                return Err(format!(
+                    "Directional modifier '{}' of '{}' would make the attacks one-sided",
+                    m, atom.text
+                ))
+            }
+            _ => return Err(format!("Unknown modifier '{}' of '{}'", m, atom.text)),
+        }
+    }
+
+    // Moves that can't capture don't attack anything
+    if moves && !captures {
+        return Ok(vec![]);
+    }
+
+    let movements = atom
+        .leaps
+        .iter()
+        .flat_map(|(dx, dy)| Movement::symmetric(*dx, *dy, range))
+        .filter(|m| {
+            let (dx, dy) = m.get_offset();
+            (!vertical && !sideways)
+                || (vertical && dy.abs() > dx.abs())
+                || (sideways && dx.abs() > dy.abs())
+        })
+        .collect::<Vec<Movement>>();
+
+    if movements.is_empty() {
+        return Err(format!(
+            "Modifiers '{}' select no moves of '{}'",
+            modifiers, atom.text
+        ));
+    }
+
+    Ok(movements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::BitBoard;
+    use crate::symmetry::Symmetry;
+
+    fn same_moves(notation: &str, piece: Piece) {
+        assert_eq!(
+            parse_betza(notation).unwrap().get_movements(),
+            piece.get_movements(),
+            "{}",
+            notation
+        );
+    }
+
+    #[test]
+    fn standard_pieces() {
+        same_moves("Q", Piece::queen());
+        same_moves("RB", Piece::queen());
+        same_moves("WWFF", Piece::queen());
+        same_moves("W0F0", Piece::queen());
+        same_moves("R", Piece::rook());
+        same_moves("B", Piece::bishop());
+        same_moves("K", Piece::king());
+        same_moves("WF", Piece::king());
+        same_moves("N", Piece::knight());
+        same_moves("(1,2)", Piece::knight());
+        same_moves("QN", Piece::amazon());
+    }
+
+    #[test]
+    fn fairy_pieces() {
+        let empty = BitBoard::new(64);
+
+        // Camel and zebra from a corner
+        let camel = parse_betza("C").unwrap();
+        assert_eq!(camel.attack_mask(8, 8, 0, &empty).count_ones(), 2);
+        let zebra = parse_betza("Z").unwrap();
+        assert_eq!(
+            zebra
+                .attack_mask(8, 8, 0, &empty)
+                .iter_ones()
+                .collect::<Vec<usize>>(),
+            vec![19, 26]
+        );
+
+        // The nightrider keeps jumping along its line until an obstacle
+        let nightrider = parse_betza("NN").unwrap();
+        assert_eq!(nightrider.attack_mask(8, 8, 0, &empty).count_ones(), 3 + 3);
+        let mut obstacles = BitBoard::new(64);
+        obstacles.set(10);
+        assert_eq!(nightrider.attack_mask(8, 8, 0, &obstacles).count_ones(), 3);
+
+        // Limited range
+        let short_rook = parse_betza("R2").unwrap();
+        assert_eq!(short_rook.attack_mask(8, 8, 0, &empty).count_ones(), 4);
+        same_moves("W2", short_rook);
+
+        // Vertical wazir and capture-only modifiers
+        let vertical = parse_betza("vW").unwrap();
+        assert_eq!(vertical.get_movements().len(), 2);
+        assert!(vertical.is_invariant(&Symmetry::ReflectVertical));
+        assert!(!vertical.is_invariant(&Symmetry::Rotate90));
+        same_moves("mNcW", Piece::leaper("Wazir", 1, 0));
+        same_moves("svN", Piece::knight());
+    }
+
+    #[test]
+    fn invalid_notation() {
+        for notation in [
+            "", "X", "W?", "fW", "lN", "qW", "vF", "W(", "(1)", "(0,0)", "(a,b)", "RR", "mW", "W v",
+        ]
+        .iter()
+        {
+            assert!(parse_betza(notation).is_err(), "{}", notation);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+const WORD_BITS: usize = 64;
+
+/// Fixed-size bit set backed by 64-bit words
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::bitboard::BitBoard;
+///
+/// let mut bits = BitBoard::new(100);
+///
+/// bits.set(3);
+/// bits.set(70);
+///
+/// assert!(bits.get(70));
+/// assert_eq!(bits.count_ones(), 2);
+/// assert_eq!(bits.iter_ones().collect::<Vec<usize>>(), vec![3, 70]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitBoard {
+    pub fn new(len: usize) -> Self {
+        BitBoard {
+            len,
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        i < self.len && self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    pub fn set(&mut self, i: usize) {
+        self.words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+    }
+
+    pub fn unset(&mut self, i: usize) {
+        self.words[i / WORD_BITS] &= !(1 << (i % WORD_BITS));
+    }
+
+    pub fn toggle(&mut self, i: usize) {
+        self.words[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = 0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn union_with(&mut self, other: &BitBoard) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitBoard) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(a, b)| *a &= b);
+    }
+
+    pub fn difference_with(&mut self, other: &BitBoard) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(a, b)| *a &= !b);
+    }
+
+    /// Iterate over the positions of the set bits, in ascending order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    None
+                } else {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(w * WORD_BITS + bit)
+                }
+            })
+        })
+    }
+
+    /// Byte of a signature holding the bit `i`, and the mask of the bit in that byte
+    pub fn signature_bit(i: usize) -> (usize, u8) {
+        (i / 8, 0x80 >> (i % 8))
+    }
+
+    /// Pack the bits MSB-first into bytes, the same layout used by the board signatures
+    pub fn to_signature(&self) -> Vec<u8> {
+        let mut signature = vec![0_u8; self.len.div_ceil(8)];
+        self.iter_ones().for_each(|i| {
+            let (byte, mask) = BitBoard::signature_bit(i);
+            signature[byte] |= mask;
+        });
+        signature
+    }
+
+    /// Unpack the first `len` bits of a signature, the inverse of `to_signature`
+    ///
+    /// Bytes missing from a short signature are read as unset bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::bitboard::BitBoard;
+    ///
+    /// let bits = BitBoard::from_signature(12, &[0x41]);
+    ///
+    /// assert_eq!(bits.iter_ones().collect::<Vec<usize>>(), vec![1, 7]);
+    /// assert_eq!(bits.to_signature(), vec![0x41, 0]);
+    /// ```
+    pub fn from_signature(len: usize, signature: &[u8]) -> Self {
+        let mut bits = BitBoard::new(len);
+        for i in 0..len.min(signature.len() * 8) {
+            let (byte, mask) = BitBoard::signature_bit(i);
+            if signature[byte] & mask != 0 {
+                bits.set(i);
+            }
+        }
+        bits
+    }
+}
@@ -1,12 +1,184 @@
-use crate::cell::{Cell, CellContent};
-use std::string::ToString;
+use crate::bitboard::BitBoard;
+use crate::cell::{Cell, CellContent, Color};
+use crate::piece::Piece;
+use crate::symmetry::Symmetry;
+use crate::topology::Topology;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Boards up to this many cells share the attack masks of a queen on every cell
+pub const MAX_MASK_CELLS: usize = 4096;
+
+/// Board sizes whose masks are kept at once, the cache is emptied once it holds more
+const MAX_MASK_SIZES: usize = 16;
+
+/// Attack masks of a queen on every cell, computed once per board size and shared
+fn get_queen_masks(lines: &AttackLines) -> Option<Arc<Vec<BitBoard>>> {
+    type Masks = HashMap<(usize, usize), Arc<Vec<BitBoard>>>;
+    static MASKS: OnceLock<Mutex<Masks>> = OnceLock::new();
+
+    let (cols, rows) = (lines.cols, lines.rows);
+    if cols * rows > MAX_MASK_CELLS {
+        return None;
+    }
+
+    let mut masks = MASKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if masks.len() >= MAX_MASK_SIZES && !masks.contains_key(&(cols, rows)) {
+        masks.clear();
+    }
+
+    let masks = masks.entry((cols, rows)).or_insert_with(|| {
+        Arc::new(
+            (0..cols * rows)
+                .map(|i| lines.walk_mask(i))
+                .collect::<Vec<BitBoard>>(),
+        )
+    });
+    Some(Arc::clone(masks))
+}
+
+/// Lines of a board, the row, column, diagonal and anti-diagonal of every cell
+///
+/// Line membership comes straight from the coordinates. A queen attacks at most
+/// `cols - 1 + 3 * (rows - 1)` cells sharing one of its lines, and boards up to
+/// `MAX_MASK_CELLS` cells precompute the mask of those cells for every cell, shared by all the
+/// boards of the same size.
+#[derive(Clone)]
+pub struct AttackLines {
+    cols: usize,
+    rows: usize,
+    masks: Option<Arc<Vec<BitBoard>>>,
+}
+
+impl AttackLines {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let mut lines = AttackLines {
+            cols,
+            rows,
+            masks: None,
+        };
+        lines.masks = get_queen_masks(&lines);
+        lines
+    }
+
+    /// Row, column, diagonal and anti-diagonal indexes of a cell
+    ///
+    /// Diagonals run from lower left to top right, anti-diagonals from top left to lower right.
+    pub fn get_lines(&self, i: usize) -> (usize, usize, usize, usize) {
+        let (x, y) = (i % self.cols, i / self.cols);
+        (y, x, x + self.rows - 1 - y, x + y)
+    }
+
+    /// Cells sharing a line with the cell `i`, each once and without the cell itself
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::AttackLines;
+    ///
+    /// let mut cells = AttackLines::new(3, 2).attacked_cells(0).collect::<Vec<usize>>();
+    /// cells.sort_unstable();
+    ///
+    /// assert_eq!(cells, vec![1, 2, 3, 4]);
+    /// ```
+    pub fn attacked_cells(&self, i: usize) -> impl Iterator<Item = usize> {
+        let (cols, rows) = (self.cols as i64, self.rows as i64);
+        let (x, y) = (i as i64 % cols, i as i64 / cols);
+
+        let row = (0..cols)
+            .filter(move |cx| *cx != x)
+            .map(move |cx| (cx + cols * y) as usize);
+
+        // Any other row crosses the column and both diagonals once each, when on the board
+        let others = (0..rows).filter(move |cy| *cy != y).flat_map(move |cy| {
+            let d = cy - y;
+            IntoIterator::into_iter([x, x + d, x - d])
+                .filter(move |cx| *cx >= 0 && *cx < cols)
+                .map(move |cx| (cx + cols * cy) as usize)
+        });
+
+        row.chain(others)
+    }
+
+    /// Precomputed mask of the cells attacked by a queen on the cell `i`, on small boards
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::AttackLines;
+    ///
+    /// let lines = AttackLines::new(3, 2);
+    ///
+    /// assert_eq!(lines.get_mask(0), Some(&lines.attack_mask(0)));
+    /// assert_eq!(AttackLines::new(1000, 1000).get_mask(0), None);
+    /// ```
+    pub fn get_mask(&self, i: usize) -> Option<&BitBoard> {
+        self.masks.as_ref().map(|m| &m[i])
+    }
+
+    /// Cells attacked by a queen placed on the cell `i`
+    pub fn attack_mask(&self, i: usize) -> BitBoard {
+        match self.get_mask(i) {
+            Some(mask) => mask.clone(),
+            None => self.walk_mask(i),
+        }
+    }
+
+    fn walk_mask(&self, i: usize) -> BitBoard {
+        let mut mask = BitBoard::new(self.cols * self.rows);
+        self.attacked_cells(i).for_each(|p| mask.set(p));
+        mask
+    }
+}
+
+impl PartialEq for AttackLines {
+    fn eq(&self, other: &Self) -> bool {
+        self.cols == other.cols && self.rows == other.rows
+    }
+}
+
+impl Eq for AttackLines {}
+
+impl fmt::Debug for AttackLines {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AttackLines")
+            .field("cols", &self.cols)
+            .field("rows", &self.rows)
+            .field("masks", &self.masks.is_some())
+            .finish()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Board {
     cols: usize,
+    rows: usize,
     cells: Vec<Cell>,
+    lines: AttackLines,
+    piece: Piece,
+    /// Kinds of piece to place and how many of each, when mixing pieces
+    mixture: Vec<(Piece, usize)>,
+    /// Kind of the piece on each occupied cell of a mixed board, as an index of `mixture`
+    kinds: HashMap<usize, usize>,
+    /// Whether the two kinds of the mixture are armies, only kept away from each other
+    peaceable: bool,
+    /// Whether pieces may go on attacked cells, attacking each other
+    attacked_placements: bool,
+    topology: Topology,
+    occupancy: BitBoard,
+    holes: BitBoard,
+    obstacles: BitBoard,
+    goal: Option<usize>,
+    occupied_rows: BitBoard,
+    occupied_columns: BitBoard,
+    occupied_diagonals: BitBoard,
+    occupied_anti_diagonals: BitBoard,
     signature: Vec<u8>,
-    rotated_signatures: Option<Vec<Vec<u8>>>,
+    equivalent_signatures: Option<Vec<Vec<u8>>>,
 }
 
 impl Board {
@@ -27,33 +199,77 @@ impl Board {
     /// assert_eq!(board.get_signature(), &vec![0_u8; 5]);
     /// ```
     pub fn new(cols: usize) -> Self {
+        Board::new_rectangle(cols, cols)
+    }
+
+    /// Constructor of a board with distinct width and height
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let mut board = Board::new_rectangle(4, 3);
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// board.toggle_cell(&4, &2).unwrap();
+    /// board.toggle_cell(&2, &3).unwrap();
+    ///
+    /// assert!(board.is_solved());
+    /// assert_eq!(board.to_multiline_string(), "0100\n0001\n1000\n");
+    /// ```
+    pub fn new_rectangle(cols: usize, rows: usize) -> Self {
         let mut board = Board {
             cols: 0,
+            rows: 0,
             cells: vec![],
+            lines: AttackLines::new(0, 0),
+            piece: Piece::queen(),
+            mixture: vec![],
+            kinds: HashMap::new(),
+            peaceable: false,
+            attacked_placements: false,
+            topology: Topology::Plane,
+            occupancy: BitBoard::new(0),
+            holes: BitBoard::new(0),
+            obstacles: BitBoard::new(0),
+            goal: None,
+            occupied_rows: BitBoard::new(0),
+            occupied_columns: BitBoard::new(0),
+            occupied_diagonals: BitBoard::new(0),
+            occupied_anti_diagonals: BitBoard::new(0),
             signature: vec![],
-            rotated_signatures: None,
+            equivalent_signatures: None,
         };
 
-        board.resize(cols);
+        board.resize_rectangle(cols, rows);
 
         board
     }
 
     pub fn resize(&mut self, cols: usize) {
-        self.cells = Vec::with_capacity(cols * cols);
+        self.resize_rectangle(cols, cols);
+    }
+
+    pub fn resize_rectangle(&mut self, cols: usize, rows: usize) {
+        self.cells = Vec::with_capacity(cols * rows);
         self.cols = cols;
-        self.signature = Vec::with_capacity(1 + cols * cols / 8);
-        self.rotated_signatures = None;
+        self.rows = rows;
+        self.lines = AttackLines::new(cols, rows);
+        self.holes = BitBoard::new(cols * rows);
+        self.obstacles = BitBoard::new(cols * rows);
+        self.goal = None;
+        self.signature = Vec::with_capacity(1 + cols * rows / 8);
+        self.equivalent_signatures = None;
 
         self.reset();
     }
 
     fn get_i_from_xy(&self, x: &usize, y: &usize) -> Result<usize, String> {
-        let i = x - 1 + self.cols * (y - 1);
-        if i >= self.cells.len() {
+        if *x < 1 || *y < 1 || *x > self.cols || *y > self.rows {
             Err(format!("Coordinates ({}, {}) out of bounds", x, y))
         } else {
-            Ok(i)
+            Ok(x - 1 + self.cols * (y - 1))
         }
     }
 
@@ -74,13 +290,16 @@ impl Board {
     pub fn get_cell_attacks(&self, x: &usize, y: &usize) -> Result<&u8, String> {
         let content = self.get_cell_content(x, y)?;
         match content {
-            CellContent::Attack(n) => Ok(&n),
+            CellContent::Attack(n) => Ok(n),
             _ => Err("Cell not attacked".to_string()),
         }
     }
 
     /// Put or remove a queen from a cell
     ///
+    /// On a mixed board the piece of the cell is removed, or a piece of the first kind of the
+    /// mixture is placed.
+    ///
     /// # Example
     ///
     /// ```
@@ -94,298 +313,1384 @@ impl Board {
     /// assert_eq!(board.get_cell_content(&2, &2).unwrap(), &CellContent::Queen);
     /// assert_eq!(board.get_signature(), &vec![0_u8, 64, 0, 0, 0, 0, 0, 0]);
     /// ```
-    pub fn toggle_cell(&mut self, x: &usize, y: &usize) -> Result<(), String> {
-        let i = self.get_i_from_xy(x, y)?;
-
-        let content = self.cells[i].toggle()?;
-        let attack = content == &CellContent::Queen;
-
-        // TODO - Improve performance, some signature masks should do the trick and predict the
-        // positions to be attacked or relieved
+    pub fn toggle_cell(&mut self, x: &usize, y: &usize) -> Result<(), String> {
+        let i = self.get_i_from_xy(x, y)?;
+        self.toggle_kind(i, self.get_kind(i))
+    }
+
+    /// Put or remove a piece of the given kind of the mixture from a cell
+    ///
+    /// Boards without a mixture only have the kind `0`, their piece. Fails if the new piece
+    /// would attack a piece already placed, even when it isn't attacked back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::piece::Piece;
+    ///
+    /// let mut board = Board::new(4);
+    /// board.set_mixture(&[(Piece::rook(), 1), (Piece::knight(), 2)]).unwrap();
+    ///
+    /// board.toggle_piece(&1, &1, 1).unwrap();
+    ///
+    /// // The rook would attack the knight without being attacked
+    /// assert!(board.toggle_piece(&1, &4, 0).is_err());
+    /// assert!(board.toggle_piece(&2, &4, 0).is_ok());
+    /// assert_eq!(board.get_signature(), &vec![0, 0x04, 0x80, 0]);
+    /// ```
+    pub fn toggle_piece(&mut self, x: &usize, y: &usize, kind: usize) -> Result<(), String> {
+        let i = self.get_i_from_xy(x, y)?;
+
+        if kind >= self.mixture.len().max(1) {
+            return Err(format!("Unknown kind of piece {}", kind));
+        }
+        if self.occupancy.get(i) && self.get_kind(i) != kind {
+            return Err(format!("Cell ({}, {}) holds another kind of piece", x, y));
+        }
+
+        self.toggle_kind(i, kind)
+    }
+
+    fn toggle_kind(&mut self, i: usize, kind: usize) -> Result<(), String> {
+        if self.peaceable {
+            return self.toggle_army(i, kind);
+        }
+        if self.attacked_placements {
+            return self.toggle_attacked(i);
+        }
+
+        let content = self.cells[i].toggle()?;
+        let attack = content == &CellContent::Queen;
+
+        if attack && !self.mixture.is_empty() {
+            let mut attacked = self.get_piece_attack_mask(&self.mixture[kind].0, i);
+            attacked.intersect_with(&self.occupancy);
+            if attacked.count_ones() > 0 {
+                self.cells[i].toggle()?;
+                return Err("The piece would attack another piece".to_string());
+            }
+            self.kinds.insert(i, kind);
+        }
+
+        for p in self.get_attacked_cells(i) {
+            self.cells[p].attack_or_relieve(&attack)?;
+        }
+
+        if !attack {
+            self.kinds.remove(&i);
+        }
+
+        self.toggle_occupancy(i, kind);
+
+        Ok(())
+    }
+
+    /// Put or remove a piece of one of the peaceable armies
+    ///
+    /// The pieces of a side may attack each other, so the cells they occupy don't count the
+    /// attacks, which are counted again when the piece is removed.
+    fn toggle_army(&mut self, i: usize, kind: usize) -> Result<(), String> {
+        let color = Color::of_kind(kind)?;
+        let attack = !self.occupancy.get(i);
+
+        if attack {
+            let mut attacked = self.get_attack_mask(i);
+            attacked.intersect_with(&self.get_army_occupancy(&color.opponent()));
+            if attacked.count_ones() > 0 {
+                return Err("The piece would attack the other side".to_string());
+            }
+            self.cells[i].occupy(&color)?;
+            self.kinds.insert(i, kind);
+        }
+
+        for p in self.get_attacked_cells(i) {
+            if self.occupancy.get(p) {
+                continue;
+            }
+            if attack {
+                self.cells[p].attack_by(&color)?;
+            } else {
+                self.cells[p].relieve_by(&color)?;
+            }
+        }
+
+        if !attack {
+            self.cells[i].toggle()?;
+            self.kinds.remove(&i);
+            self.count_army_attacks(i)?;
+        }
+
+        self.toggle_occupancy(i, kind);
+
+        Ok(())
+    }
+
+    /// Put or remove a piece on a board allowing attacked placements
+    ///
+    /// The cells occupied don't count the attacks, which are counted again when the piece is
+    /// removed.
+    fn toggle_attacked(&mut self, i: usize) -> Result<(), String> {
+        let attack = !self.occupancy.get(i);
+        let attacked = self.get_attacked_cells(i);
+
+        // Cells count at most 255 attacks, checked before the board changes
+        let too_many = if attack {
+            attacked.iter().any(|p| {
+                !self.occupancy.get(*p) && self.cells[*p].get_content() == &CellContent::Attack(255)
+            })
+        } else {
+            attacked.iter().filter(|p| self.occupancy.get(**p)).count() > 255
+        };
+        if too_many {
+            return Err("Too many attacks on a cell".to_string());
+        }
+
+        if attack {
+            self.cells[i].place()?;
+        }
+
+        for p in &attacked {
+            if !self.occupancy.get(*p) {
+                self.cells[*p].attack_or_relieve(&attack)?;
+            }
+        }
+
+        if !attack {
+            self.cells[i].toggle()?;
+            for p in &attacked {
+                if self.occupancy.get(*p) {
+                    self.cells[i].attack()?;
+                }
+            }
+        }
+
+        self.toggle_occupancy(i, 0);
+
+        Ok(())
+    }
+
+    /// Count the attacks of both armies on the empty cell `i`
+    fn count_army_attacks(&mut self, i: usize) -> Result<(), String> {
+        for p in self.occupancy.iter_ones().collect::<Vec<usize>>() {
+            if p != i && self.get_attack_mask(p).get(i) {
+                let color = Color::of_kind(self.get_kind(p))?;
+                self.cells[i].attack_by(&color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cells occupied by the pieces of a side
+    fn get_army_occupancy(&self, color: &Color) -> BitBoard {
+        let mut army = BitBoard::new(self.cols * self.rows);
+        self.kinds
+            .iter()
+            .filter(|(_, k)| **k == color.get_kind())
+            .for_each(|(i, _)| army.set(*i));
+        army
+    }
+
+    fn toggle_occupancy(&mut self, i: usize, kind: usize) {
+        let (row, column, diagonal, anti_diagonal) = self.lines.get_lines(i);
+        self.occupancy.toggle(i);
+        self.occupied_rows.toggle(row);
+        self.occupied_columns.toggle(column);
+        self.occupied_diagonals.toggle(diagonal);
+        self.occupied_anti_diagonals.toggle(anti_diagonal);
+
+        // Update signature, single XOR will do the trick
+        let plane = kind * (self.cols * self.rows).div_ceil(8);
+        let (byte, mask) = BitBoard::signature_bit(i);
+        self.signature[plane + byte] ^= mask;
+        self.equivalent_signatures = None;
+    }
+
+    pub fn try_toggle_cell(&mut self, x: &usize, y: &usize) {
+        let _ = self.toggle_cell(x, y);
+    }
+
+    /// Cells attacked by a piece on the cell `i`
+    ///
+    /// Queens on a plane without obstacles come straight from their lines, otherwise the
+    /// movements of the piece are walked until they reach an obstacle. Holes are never
+    /// attacked. On a mixed board the piece is the one placed on the cell, if any.
+    pub fn get_attack_mask(&self, i: usize) -> BitBoard {
+        self.get_piece_attack_mask(self.get_piece_at(i), i)
+    }
+
+    /// Cells attacked by the piece on the cell `i`, walking the lines of a queen when it can
+    /// instead of building a mask of the whole board
+    fn get_attacked_cells(&self, i: usize) -> Vec<usize> {
+        if self.uses_lines() {
+            match self.lines.get_mask(i) {
+                Some(mask) => mask.iter_ones().filter(|p| !self.holes.get(*p)).collect(),
+                None => self
+                    .lines
+                    .attacked_cells(i)
+                    .filter(|p| !self.holes.get(*p))
+                    .collect(),
+            }
+        } else {
+            self.get_attack_mask(i).iter_ones().collect()
+        }
+    }
+
+    fn get_piece_attack_mask(&self, piece: &Piece, i: usize) -> BitBoard {
+        let mut mask = if self.uses_lines() {
+            self.lines.attack_mask(i)
+        } else {
+            piece.attack_mask_on(&self.topology, self.cols, self.rows, i, &self.obstacles)
+        };
+        mask.difference_with(&self.holes);
+        mask
+    }
+
+    /// Turn a cell into a hole, or restore a hole
+    ///
+    /// Holes can't hold queens but don't stop the attacks passing through them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut board = Board::new(4);
+    ///
+    /// board.toggle_hole(&2, &2).unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_content(&2, &2).unwrap(), &CellContent::Blocked);
+    /// assert_eq!(board.get_cell_attacks(&3, &3).unwrap(), &1);
+    /// assert!(board.toggle_cell(&2, &2).is_err());
+    ///
+    /// board.toggle_hole(&2, &2).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_attacks(&2, &2).unwrap(), &1);
+    /// ```
+    pub fn toggle_hole(&mut self, x: &usize, y: &usize) -> Result<(), String> {
+        let i = self.get_i_from_xy(x, y)?;
+
+        if self.holes.get(i) {
+            self.holes.unset(i);
+
+            if self.peaceable {
+                self.cells[i].unblock(0)?;
+                self.count_army_attacks(i)?;
+                self.equivalent_signatures = None;
+                return Ok(());
+            }
+
+            // Attacks are symmetric, the queens attacking the cell are the ones it would attack,
+            // unless the kinds of piece are mixed
+            let attackers = if self.mixture.is_empty() {
+                self.get_attacked_cells(i)
+                    .into_iter()
+                    .filter(|p| self.occupancy.get(*p))
+                    .count()
+            } else {
+                self.occupancy
+                    .iter_ones()
+                    .filter(|p| self.get_attack_mask(*p).get(i))
+                    .count()
+            };
+
+            self.cells[i].unblock(attackers as u8)?;
+        } else {
+            self.cells[i].block()?;
+            self.holes.set(i);
+        }
+
+        self.equivalent_signatures = None;
+
+        Ok(())
+    }
+
+    /// Put an obstacle on a cell, or remove it
+    ///
+    /// Obstacles can't hold queens and stop the lines of attack, so more queens than rows may
+    /// fit on the board. Fails if removing the obstacle would let two queens attack each other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut board = Board::new(3);
+    ///
+    /// board.toggle_obstacle(&2, &1).unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// board.toggle_cell(&3, &1).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_content(&2, &1).unwrap(), &CellContent::Obstacle);
+    /// assert!(board.toggle_obstacle(&2, &1).is_err());
+    /// ```
+    pub fn toggle_obstacle(&mut self, x: &usize, y: &usize) -> Result<(), String> {
+        let i = self.get_i_from_xy(x, y)?;
+
+        if self.occupancy.get(i) || self.holes.get(i) {
+            return Err(format!("Cell ({}, {}) not available for an obstacle", x, y));
+        }
+
+        self.obstacles.toggle(i);
+        if let Err(e) = self.rebuild_cells() {
+            self.obstacles.toggle(i);
+            self.rebuild_cells()?;
+            return Err(e);
+        }
+
+        self.equivalent_signatures = None;
+
+        Ok(())
+    }
+
+    /// Whether every piece attacks whole lines, so the line bits tell the available cells
+    fn uses_lines(&self) -> bool {
+        self.obstacles.is_empty()
+            && self.piece.is_queen()
+            && self.mixture.is_empty()
+            && self.topology == Topology::Plane
+    }
+
+    /// Change the kind of piece placed on the board
+    ///
+    /// Fails if the pieces already placed would attack each other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::piece::Piece;
+    ///
+    /// let mut board = Board::new(3);
+    ///
+    /// board.set_piece(Piece::knight()).unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// board.toggle_cell(&2, &1).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_attacks(&3, &2).unwrap(), &1);
+    /// assert!(board.set_piece(Piece::king()).is_err());
+    /// ```
+    pub fn set_piece(&mut self, piece: Piece) -> Result<(), String> {
+        let previous = std::mem::replace(&mut self.piece, piece);
+        if let Err(e) = self.rebuild_cells() {
+            self.piece = previous;
+            self.rebuild_cells()?;
+            return Err(e);
+        }
+
+        self.equivalent_signatures = None;
+
+        Ok(())
+    }
+
+    pub fn get_piece(&self) -> &Piece {
+        &self.piece
+    }
+
+    /// Place several kinds of piece at once, with how many of each must be placed
+    ///
+    /// The board is solved once every piece of the mixture is placed, and its signature holds
+    /// one block of bits per kind, in the order of the mixture. An empty mixture goes back to
+    /// placing the piece of the board. Fails if there are pieces on the board.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::piece::Piece;
+    ///
+    /// let mut board = Board::new(4);
+    /// board.set_mixture(&[(Piece::queen(), 1), (Piece::knight(), 2)]).unwrap();
+    ///
+    /// board.toggle_piece(&1, &1, 0).unwrap();
+    /// board.toggle_piece(&2, &4, 1).unwrap();
+    /// assert!(!board.is_solved());
+    ///
+    /// board.toggle_piece(&3, &4, 1).unwrap();
+    /// assert!(board.is_solved());
+    /// assert_eq!(board.get_signature(), &vec![0x80, 0, 0, 0x06]);
+    /// ```
+    pub fn set_mixture(&mut self, mixture: &[(Piece, usize)]) -> Result<(), String> {
+        if self.occupancy.count_ones() > 0 {
+            return Err("Pieces must be removed before changing the mixture".to_string());
+        }
+
+        self.mixture = mixture.to_vec();
+        self.peaceable = false;
+        self.attacked_placements = false;
+        self.reset();
+
+        Ok(())
+    }
+
+    /// Replace the pieces with the ones of a signature, as given by `get_signature`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let mut board = Board::new(4);
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// board.load_signature(&[0x41, 0]).unwrap();
+    ///
+    /// assert_eq!(board.get_queens(), vec![(2, 1), (4, 2)]);
+    /// ```
+    pub fn load_signature(&mut self, signature: &[u8]) -> Result<(), String> {
+        let cells = self.cols * self.rows;
+        let plane = cells.div_ceil(8);
+        self.reset();
+
+        for kind in 0..self.mixture.len().max(1) {
+            let pieces =
+                BitBoard::from_signature(cells, signature.get(kind * plane..).unwrap_or(&[]));
+            for i in pieces.iter_ones() {
+                self.toggle_piece(&(i % self.cols + 1), &(i / self.cols + 1), kind)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_mixture(&self) -> &Vec<(Piece, usize)> {
+        &self.mixture
+    }
+
+    /// Place two armies of `count` pieces each, that must not attack the other side
+    ///
+    /// Pieces of the same side may attack each other. The armies are the two kinds of a
+    /// mixture of the piece of the board, white first, see `Color`. Fails if there are pieces
+    /// on the board.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::{CellContent, Color};
+    ///
+    /// let mut board = Board::new(4);
+    /// board.set_armies(2).unwrap();
+    ///
+    /// board.toggle_piece(&1, &1, Color::White.get_kind()).unwrap();
+    /// board.toggle_piece(&2, &1, Color::White.get_kind()).unwrap();
+    /// assert_eq!(board.get_cell_content(&2, &2).unwrap(), &CellContent::Armies(2, 0));
+    ///
+    /// assert!(board.toggle_piece(&4, &4, Color::Black.get_kind()).is_err());
+    /// board.toggle_piece(&3, &4, Color::Black.get_kind()).unwrap();
+    /// board.toggle_piece(&4, &2, Color::Black.get_kind()).unwrap();
+    ///
+    /// assert!(board.is_solved());
+    /// assert_eq!(board.get_army(&Color::Black), vec![(4, 2), (3, 4)]);
+    /// ```
+    pub fn set_armies(&mut self, count: usize) -> Result<(), String> {
+        let piece = self.piece.clone();
+        self.set_mixture(&[(piece.clone(), count), (piece, count)])?;
+        self.peaceable = true;
+
+        Ok(())
+    }
+
+    /// Let the pieces go on attacked cells and attack each other, as dominating pieces do
+    ///
+    /// The cells occupied don't count the attacks. Fails if there are pieces on the board or
+    /// a mixture, and changing the mixture disallows it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut board = Board::new(3);
+    /// board.set_attacked_placements(true).unwrap();
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// board.toggle_cell(&2, &2).unwrap();
+    /// assert_eq!(board.get_cell_content(&3, &3).unwrap(), &CellContent::Attack(2));
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// assert_eq!(board.get_cell_content(&1, &1).unwrap(), &CellContent::Attack(1));
+    /// assert_eq!(board.get_available_cells().len(), 8);
+    /// ```
+    pub fn set_attacked_placements(&mut self, allowed: bool) -> Result<(), String> {
+        if self.occupancy.count_ones() > 0 {
+            return Err("Pieces must be removed before allowing attacked placements".to_string());
+        }
+        if allowed && !self.mixture.is_empty() {
+            return Err("Attacked placements need a single kind of piece".to_string());
+        }
+
+        self.attacked_placements = allowed;
+        self.reset();
+
+        Ok(())
+    }
+
+    /// Whether pieces may go on attacked cells, see `set_attacked_placements`
+    pub fn allows_attacked_placements(&self) -> bool {
+        self.attacked_placements
+    }
+
+    /// Whether the board places two peaceable armies
+    pub fn is_peaceable(&self) -> bool {
+        self.peaceable
+    }
+
+    /// Coordinates of the pieces of a side, in cell order
+    pub fn get_army(&self, color: &Color) -> Vec<(usize, usize)> {
+        self.occupancy
+            .iter_ones()
+            .filter(|i| self.get_kind(*i) == color.get_kind())
+            .map(|i| (i % self.cols + 1, i / self.cols + 1))
+            .collect()
+    }
+
+    /// Kind of the piece on the cell `i`, `0` when empty or not mixing pieces
+    fn get_kind(&self, i: usize) -> usize {
+        self.kinds.get(&i).cloned().unwrap_or(0)
+    }
+
+    fn get_piece_at(&self, i: usize) -> &Piece {
+        match self.kinds.get(&i) {
+            Some(kind) => &self.mixture[*kind].0,
+            None => &self.piece,
+        }
+    }
+
+    /// Coordinates of the placed pieces with their kind, in cell order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::piece::Piece;
+    ///
+    /// let mut board = Board::new(3);
+    /// board.set_mixture(&[(Piece::king(), 1), (Piece::rook(), 1)]).unwrap();
+    ///
+    /// board.toggle_piece(&2, &3, 1).unwrap();
+    /// board.toggle_piece(&1, &1, 0).unwrap();
+    ///
+    /// let placement = board
+    ///     .get_placement()
+    ///     .iter()
+    ///     .map(|(x, y, p)| format!("{} at ({}, {})", p, x, y))
+    ///     .collect::<Vec<String>>();
+    ///
+    /// assert_eq!(placement, vec!["King at (1, 1)", "Rook at (2, 3)"]);
+    /// ```
+    pub fn get_placement(&self) -> Vec<(usize, usize, &Piece)> {
+        self.occupancy
+            .iter_ones()
+            .map(|i| (i % self.cols + 1, i / self.cols + 1, self.get_piece_at(i)))
+            .collect()
+    }
+
+    /// Cells where a piece of each kind can be placed, with the kind
+    ///
+    /// A piece of a mixture can't go where it would attack a piece already placed, and the
+    /// kinds without pieces left are skipped. Boards without a mixture only have the kind `0`.
+    pub fn get_available_placements(&self) -> Vec<(Cell, usize)> {
+        if self.peaceable {
+            return self.get_available_army_placements();
+        }
+
+        let cells = self.get_available_cells();
+        if self.mixture.is_empty() {
+            return cells.into_iter().map(|c| (c, 0)).collect();
+        }
+
+        let mut placements = vec![];
+        for (kind, (piece, count)) in self.mixture.iter().enumerate() {
+            if self.kinds.values().filter(|k| **k == kind).count() >= *count {
+                continue;
+            }
+
+            for c in &cells {
+                let mut attacked = self.get_piece_attack_mask(piece, *c.get_xyi().2);
+                attacked.intersect_with(&self.occupancy);
+                if attacked.count_ones() == 0 {
+                    placements.push((c.clone(), kind));
+                }
+            }
+        }
+
+        placements
+    }
+
+    fn get_available_army_placements(&self) -> Vec<(Cell, usize)> {
+        let mut placements = vec![];
+
+        for color in Color::ALL.iter() {
+            let kind = color.get_kind();
+            if self.kinds.values().filter(|k| **k == kind).count() >= self.mixture[kind].1 {
+                continue;
+            }
+
+            let opponent = self.get_army_occupancy(&color.opponent());
+            for c in self.cells.iter().filter(|c| c.is_available_for(color)) {
+                let (x, y, i) = c.get_xyi();
+                let mut attacked = self.get_attack_mask(*i);
+                attacked.intersect_with(&opponent);
+                if attacked.count_ones() == 0 {
+                    placements.push((Cell::new(*x, *y, *i), kind));
+                }
+            }
+        }
+
+        placements
+    }
+
+    /// Glue the edges of the board, so the lines of attack wrap around
+    ///
+    /// Fails if the pieces already placed would attack each other. Only the transforms that
+    /// keep the gluing remain symmetries of the board.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::topology::Topology;
+    ///
+    /// let mut board = Board::new(5);
+    ///
+    /// board.set_topology(Topology::Torus).unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_attacks(&5, &5).unwrap(), &1);
+    /// assert!(board.toggle_cell(&5, &2).is_err());
+    /// ```
+    pub fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        let previous = std::mem::replace(&mut self.topology, topology);
+        if let Err(e) = self.rebuild_cells() {
+            self.topology = previous;
+            self.rebuild_cells()?;
+            return Err(e);
+        }
+
+        self.equivalent_signatures = None;
+
+        Ok(())
+    }
+
+    pub fn get_topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    /// Recompute the content of every cell from the pieces, holes and obstacles
+    fn rebuild_cells(&mut self) -> Result<(), String> {
+        self.cells.clear();
+        for y in 1..=self.rows {
+            for x in 1..=self.cols {
+                let i = self.cells.len();
+                self.cells.push(Cell::new(x, y, i));
+            }
+        }
+
+        for i in self.holes.iter_ones() {
+            self.cells[i].block()?;
+        }
+
+        for i in self.obstacles.iter_ones() {
+            self.cells[i].obstruct()?;
+        }
+
+        if self.peaceable {
+            return self.rebuild_army_cells();
+        }
+        if self.attacked_placements {
+            return self.rebuild_attacked_cells();
+        }
+
+        for i in self.occupancy.iter_ones() {
+            self.cells[i].toggle()?;
+            for p in self.get_attacked_cells(i) {
+                self.cells[p]
+                    .attack()
+                    .map_err(|_| "Pieces would attack each other".to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count the attacks of the armies on the cells, after the holes and obstacles
+    fn rebuild_army_cells(&mut self) -> Result<(), String> {
+        for i in self.occupancy.iter_ones() {
+            self.cells[i].toggle()?;
+        }
+
+        for i in self.occupancy.iter_ones() {
+            let color = Color::of_kind(self.get_kind(i))?;
+            for p in self.get_attack_mask(i).iter_ones() {
+                if !self.occupancy.get(p) {
+                    self.cells[p].attack_by(&color)?;
+                } else if self.get_kind(p) != color.get_kind() {
+                    return Err("Armies would attack each other".to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count the attacks on the cells left empty, after the holes and obstacles
+    fn rebuild_attacked_cells(&mut self) -> Result<(), String> {
+        for i in self.occupancy.iter_ones() {
+            self.cells[i].toggle()?;
+        }
+
+        for i in self.occupancy.iter_ones() {
+            for p in self.get_attacked_cells(i) {
+                if !self.occupancy.get(p) {
+                    self.cells[p].attack()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-initialize the board
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut  board = Board::new(6);
+    ///
+    /// board.toggle_cell(&1, &2).unwrap();
+    /// board.reset();
+    ///
+    /// board.get_cells()
+    ///     .iter()
+    ///     .for_each(|c| assert_eq!(c.get_content(), &CellContent::Empty));
+    ///
+    /// assert_eq!(board.get_signature(), &vec![0_u8; 5]);
+    /// ```
+    pub fn reset(&mut self) {
+        let lines = (self.cols + self.rows).saturating_sub(1);
+        self.occupancy = BitBoard::new(self.cols * self.rows);
+        self.occupied_rows = BitBoard::new(self.rows);
+        self.occupied_columns = BitBoard::new(self.cols);
+        self.occupied_diagonals = BitBoard::new(lines);
+        self.occupied_anti_diagonals = BitBoard::new(lines);
+
+        self.signature = self
+            .occupancy
+            .to_signature()
+            .repeat(self.mixture.len().max(1));
+        self.equivalent_signatures = None;
+        self.kinds.clear();
+
+        self.rebuild_cells()
+            .expect("Boards without queens can always be rebuilt");
+    }
+
+    pub fn mirror(&self) -> Result<Board, String> {
+        self.reflect_diagonal()
+    }
+
+    pub fn get_cols(&self) -> &usize {
+        &self.cols
+    }
+
+    pub fn get_rows(&self) -> &usize {
+        &self.rows
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.cols == self.rows
+    }
+
+    /// Number of pieces of a complete solution
+    ///
+    /// Defaults to one piece per row or column, whichever is fewer, or to the size of the
+    /// mixture when mixing pieces.
+    pub fn get_goal(&self) -> usize {
+        if !self.mixture.is_empty() {
+            return self.mixture.iter().map(|(_, count)| count).sum();
+        }
+
+        self.goal.unwrap_or_else(|| self.cols.min(self.rows))
+    }
+
+    pub fn set_goal(&mut self, goal: usize) {
+        self.goal = Some(goal);
+    }
+
+    /// Upper bound of the number of non-attacking pieces
+    ///
+    /// Each stretch of a line ridden by the piece, between obstacles, holds at most one piece.
+    /// Pieces that don't ride are only bounded by the number of free cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::piece::Piece;
+    ///
+    /// let mut board = Board::new(4);
+    ///
+    /// assert_eq!(board.get_piece_bound(), 4);
+    ///
+    /// board.toggle_obstacle(&2, &2).unwrap();
+    /// board.toggle_obstacle(&3, &3).unwrap();
+    ///
+    /// assert_eq!(board.get_piece_bound(), 6);
+    ///
+    /// board.set_piece(Piece::knight()).unwrap();
+    ///
+    /// assert_eq!(board.get_piece_bound(), 14);
+    /// ```
+    pub fn get_piece_bound(&self) -> usize {
+        let free = self.cols * self.rows - self.holes.count_ones() - self.obstacles.count_ones();
+
+        self.piece
+            .get_movements()
+            .iter()
+            .filter(|m| m.get_range().is_none())
+            .map(|m| self.count_segments(m.get_offset()))
+            .fold(free, |bound, segments| bound.min(segments))
+    }
+
+    /// Number of stretches along the lines of direction `(dx, dy)`, split by the obstacles,
+    /// that have at least one cell able to hold a piece
+    fn count_segments(&self, (dx, dy): (i64, i64)) -> usize {
+        let cols = self.cols as i64;
+
+        // Lines are walked both ways, so each cell belongs to one stretch per direction up to
+        // its sign. The direction changes along the lines of the Klein bottle.
+        let key = |i: usize, dx: i64, dy: i64| {
+            if (dx, dy) < (0, 0) {
+                (i, -dx, -dy)
+            } else {
+                (i, dx, dy)
+            }
+        };
+
+        let mut visited = HashSet::new();
+        let mut count = 0;
+
+        for i in 0..self.cols * self.rows {
+            if self.obstacles.get(i) || !visited.insert(key(i, dx, dy)) {
+                continue;
+            }
+
+            let mut holds = !self.holes.get(i);
+            for (sx, sy) in [(dx, dy), (-dx, -dy)].iter() {
+                let (mut x, mut y, mut sx, mut sy) =
+                    ((i % self.cols) as i64, (i / self.cols) as i64, *sx, *sy);
+
+                while let Some(next) = self.topology.step(self.cols, self.rows, x, y, sx, sy) {
+                    (x, y, sx, sy) = next;
+
+                    let p = (x + cols * y) as usize;
+                    if self.obstacles.get(p) || !visited.insert(key(p, sx, sy)) {
+                        break;
+                    }
+                    holds |= !self.holes.get(p);
+                }
+            }
+
+            if holds {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    pub fn get_cells(&self) -> &Vec<Cell> {
+        &self.cells
+    }
+
+    pub fn get_occupancy(&self) -> &BitBoard {
+        &self.occupancy
+    }
+
+    pub fn get_holes(&self) -> &BitBoard {
+        &self.holes
+    }
+
+    pub fn has_holes(&self) -> bool {
+        !self.holes.is_empty()
+    }
+
+    pub fn get_obstacles(&self) -> &BitBoard {
+        &self.obstacles
+    }
+
+    pub fn has_obstacles(&self) -> bool {
+        !self.obstacles.is_empty()
+    }
+
+    /// Obstacle mask packed the same way as the signature
+    pub fn get_obstacle_signature(&self) -> Vec<u8> {
+        self.obstacles.to_signature()
+    }
+
+    /// Hole mask packed the same way as the signature
+    pub fn get_hole_signature(&self) -> Vec<u8> {
+        self.holes.to_signature()
+    }
+
+    /// Coordinates of the placed queens, in cell order
+    pub fn get_queens(&self) -> Vec<(usize, usize)> {
+        self.occupancy
+            .iter_ones()
+            .map(|i| (i % self.cols + 1, i / self.cols + 1))
+            .collect()
+    }
+
+    /// Whether every cell able to hold a piece holds one or is attacked
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let mut board = Board::new(3);
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// assert!(!board.is_dominated());
+    ///
+    /// board.toggle_hole(&2, &3).unwrap();
+    /// board.toggle_hole(&3, &2).unwrap();
+    /// assert!(board.is_dominated());
+    /// ```
+    pub fn is_dominated(&self) -> bool {
+        self.cells.iter().all(|c| !c.is_empty())
+    }
+
+    /// Cells that are neither occupied nor attacked
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let mut board = Board::new(4);
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// let available = board
+    ///     .get_available_cells()
+    ///     .iter()
+    ///     .map(|c| (*c.get_xyi().0, *c.get_xyi().1))
+    ///     .collect::<Vec<(usize, usize)>>();
+    ///
+    /// assert_eq!(available, vec![(3, 2), (4, 2), (2, 3), (4, 3), (2, 4), (3, 4)]);
+    /// ```
+    pub fn get_available_cells(&self) -> Vec<Cell> {
+        // Lines may hold several pieces once obstacles stop the attacks, or the piece doesn't
+        // attack the whole line
+        if !self.uses_lines() || self.attacked_placements {
+            return self
+                .cells
+                .iter()
+                .filter(|c| c.is_empty() || (self.attacked_placements && c.is_attacked()))
+                .map(|c| {
+                    let (x, y, i) = c.get_xyi();
+                    Cell::new(*x, *y, *i)
+                })
+                .collect();
+        }
+
+        let mut cells = vec![];
+
+        for y in (0..self.rows).filter(|y| !self.occupied_rows.get(*y)) {
+            for x in (0..self.cols).filter(|x| !self.occupied_columns.get(*x)) {
+                let i = x + self.cols * y;
+                let (_, _, diagonal, anti_diagonal) = self.lines.get_lines(i);
+
+                if !self.occupied_diagonals.get(diagonal)
+                    && !self.occupied_anti_diagonals.get(anti_diagonal)
+                    && !self.holes.get(i)
+                {
+                    cells.push(Cell::new(x + 1, y + 1, i));
+                }
+            }
+        }
+
+        cells
+    }
+
+    pub fn get_signature(&self) -> &Vec<u8> {
+        &self.signature
+    }
+
+    pub fn to_relative_coord(&self, x: &usize, y: &usize) -> (i32, i32) {
+        let (fx, fy) = (*x as f64, *y as f64);
+        let center_x = (self.cols as f64) / 2.0 + 0.5;
+        let center_y = (self.rows as f64) / 2.0 + 0.5;
+
+        let mut rx = ((fx - center_x).abs() + 0.5) as i32;
+        let mut ry = ((fy - center_y).abs() + 0.5) as i32;
+
+        if fx < center_x {
+            rx = -rx;
+        }
+
+        if fy < center_y {
+            ry = -ry;
+        }
+
+        (rx, ry)
+    }
+
+    /// Return the signatures of the board under every transform of its symmetry group
+    ///
+    /// Square boards have the 8 transforms of `Symmetry::ALL`, rectangular boards the 4 of
+    /// `Symmetry::RECTANGLE`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut board = Board::new(8);
+    ///
+    /// board.toggle_cell(&1, &2).unwrap();
+    /// board.toggle_cell(&2, &5).unwrap();
+    /// board.toggle_cell(&3, &7).unwrap();
+    /// board.toggle_cell(&4, &4).unwrap();
+    /// board.toggle_cell(&5, &1).unwrap();
+    /// board.toggle_cell(&6, &8).unwrap();
+    /// board.toggle_cell(&7, &6).unwrap();
+    /// board.toggle_cell(&8, &3).unwrap();
+    ///
+    /// let rs = board.get_equivalent_signatures().unwrap();
+    ///
+    /// assert_eq!(rs[0], vec![8, 128, 1, 16, 64, 2, 32, 4]);
+    /// assert_eq!(rs[1], vec![2, 16, 64, 8, 1, 128, 32, 4]);
+    /// assert_eq!(rs[2], vec![32, 4, 64, 2, 8, 128, 1, 16]);
+    /// assert_eq!(rs[3], vec![32, 4, 1, 128, 16, 2, 8, 64]);
+    /// assert_eq!(rs[4], vec![64, 8, 2, 16, 128, 1, 4, 32]);
+    /// assert_eq!(rs[5], vec![16, 1, 128, 8, 2, 64, 4, 32]);
+    /// assert_eq!(rs[6], vec![4, 32, 128, 1, 8, 64, 16, 2]);
+    /// assert_eq!(rs[7], vec![4, 32, 2, 64, 16, 1, 128, 8]);
+    /// ```
+    pub fn get_equivalent_signatures(&mut self) -> Result<Vec<Vec<u8>>, String> {
+        if let Some(s) = &self.equivalent_signatures {
+            return Ok(s.clone());
+        }
 
-        let (xs, ys, cs) = (*x as i32, *y as i32, self.cols as i32);
+        let mut signatures = vec![];
 
-        // Horizontal
-        {
-            let min = ((ys - 1) * cs) as usize;
-            let max = (ys * cs) as usize;
-            for p in min..max {
-                if p != i {
-                    self.cells[p].attack_or_relieve(&attack)?;
-                }
+        for symmetry in self.get_symmetries().iter() {
+            let mut transformed =
+                vec![BitBoard::new(self.cols * self.rows); self.mixture.len().max(1)];
+            for i in self.occupancy.iter_ones() {
+                let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+                let (tx, ty) = symmetry.apply(self.cols, self.rows, &x, &y);
+                transformed[self.get_kind(i)].set(self.get_i_from_xy(&tx, &ty)?);
             }
+            signatures.push(transformed.iter().flat_map(|t| t.to_signature()).collect());
         }
 
-        // Vertical
-        {
-            let min = (xs - 1) as usize;
-            let max = (cs * (cs - 1) + xs) as usize;
-            for p in (min..max).step_by(self.cols) {
-                if p != i {
-                    self.cells[p].attack_or_relieve(&attack)?;
-                }
-            }
-        }
+        self.equivalent_signatures = Some(signatures.clone());
 
-        // Diagonal lower left -> top right
-        {
-            let (min, max) = if xs + ys <= cs + 1 {
-                ((xs + ys - 2) as usize, (cs * (xs + ys - 2) + 1) as usize)
-            } else {
-                (
-                    (cs * (ys + xs - cs) - 1) as usize,
-                    (xs + ys + cs * (cs - 2)) as usize,
-                )
-            };
-            for p in (min..max).step_by(self.cols - 1) {
-                if p != i {
-                    self.cells[p].attack_or_relieve(&attack)?;
-                }
-            }
-        }
+        Ok(signatures)
+    }
 
-        // Diagonal top left -> lower right
-        {
-            let (min, max) = if xs >= ys {
-                ((xs - ys) as usize, (cs * (cs + ys - xs)) as usize)
-            } else {
-                ((cs * (ys - xs)) as usize, (xs - ys + cs.pow(2)) as usize)
-            };
-            for p in (min..max).step_by(self.cols + 1) {
-                if p != i {
-                    self.cells[p].attack_or_relieve(&attack)?;
-                }
-            }
-        }
+    /// Lexicographically smallest signature of the orbit of the board
+    pub fn get_canonical_signature(&mut self) -> Result<Vec<u8>, String> {
+        Ok(self
+            .get_equivalent_signatures()?
+            .into_iter()
+            .min()
+            .unwrap_or_default())
+    }
 
-        // Update signature, single XOR will do the trick
-        let sig_pos = i / 8;
-        let sig_mask = 2_u8.pow((7 - (i - sig_pos * 8)) as u32);
-        self.signature[sig_pos] ^= sig_mask;
-        self.rotated_signatures = None;
+    /// Transforms that keep the dimensions, the holes, the obstacles and the piece of the board
+    pub fn get_symmetries(&self) -> Vec<Symmetry> {
+        let keeps = |s: &Symmetry, mask: &BitBoard| {
+            mask.iter_ones().all(|i| {
+                let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+                let (tx, ty) = s.apply(self.cols, self.rows, &x, &y);
+                mask.get(tx - 1 + self.cols * (ty - 1))
+            })
+        };
 
-        Ok(())
+        self.topology
+            .group(self.cols, self.rows)
+            .iter()
+            .filter(|s| {
+                keeps(s, &self.holes)
+                    && keeps(s, &self.obstacles)
+                    && self.piece.is_invariant(s)
+                    && self.mixture.iter().all(|(p, _)| p.is_invariant(s))
+            })
+            .cloned()
+            .collect()
     }
 
-    pub fn try_toggle_cell(&mut self, x: &usize, y: &usize) {
-        match self.toggle_cell(x, y) {
-            _ => {},
-        }
+    /// Transforms that map the board onto itself
+    pub fn get_stabilizer(&self) -> Vec<Symmetry> {
+        self.get_symmetries()
+            .iter()
+            .filter(|s| {
+                self.occupancy.iter_ones().all(|i| {
+                    let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+                    let (tx, ty) = s.apply(self.cols, self.rows, &x, &y);
+                    let t = tx - 1 + self.cols * (ty - 1);
+                    self.occupancy.get(t) && self.get_kind(t) == self.get_kind(i)
+                })
+            })
+            .cloned()
+            .collect()
     }
 
-    /// Re-initialize the board
+    /// Number of distinct boards equivalent to this one: 1, 2, 4 or 8
+    pub fn get_orbit_size(&self) -> usize {
+        self.get_symmetries().len() / self.get_stabilizer().len()
+    }
+
+    /// Copy of the board with the queens, holes and obstacles moved by a transform
     ///
     /// # Example
     ///
     /// ```
     /// use andaluz_core::board::Board;
-    /// use andaluz_core::cell::CellContent;
+    /// use andaluz_core::symmetry::Symmetry;
     ///
-    /// let mut  board = Board::new(6);
+    /// let mut board = Board::new(4);
     ///
     /// board.toggle_cell(&1, &2).unwrap();
-    /// board.reset();
     ///
-    /// board.get_cells()
-    ///     .iter()
-    ///     .for_each(|c| assert_eq!(c.get_content(), &CellContent::Empty));
+    /// let rotated = board.transform(&Symmetry::Rotate90).unwrap();
     ///
-    /// assert_eq!(board.get_signature(), &vec![0_u8; 5]);
+    /// assert_eq!(rotated.get_queens(), vec![(3, 1)]);
     /// ```
-    pub fn reset(&mut self) {
-        self.cells.clear();
-        for y in 1..=self.cols {
-            for x in 1..=self.cols {
-                let i = self.cells.len();
-                self.cells.push(Cell::new(x, y, i));
-            }
+    pub fn transform(&self, symmetry: &Symmetry) -> Result<Board, String> {
+        if !self.topology.group(self.cols, self.rows).contains(symmetry) {
+            return Err(format!(
+                "{:?} is not a symmetry of a {}x{} board",
+                symmetry, self.cols, self.rows
+            ));
         }
 
-        self.signature.clear();
-        let size = (((self.cols * self.cols) as f64) / 8.0_f64).ceil() as usize;
-        for _ in 0..size {
-            self.signature.push(0);
+        let mut board = Board::new_rectangle(self.cols, self.rows);
+        board.piece = self.piece.clone();
+        board.topology = self.topology;
+        board.goal = self.goal;
+        board.set_mixture(&self.mixture)?;
+        board.peaceable = self.peaceable;
+        board.attacked_placements = self.attacked_placements;
+
+        for i in self.holes.iter_ones() {
+            let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+            let (tx, ty) = symmetry.apply(self.cols, self.rows, &x, &y);
+            board.toggle_hole(&tx, &ty)?;
         }
-    }
 
-    pub fn mirror(&self) -> Result<Board, String> {
-        let mut board = Board::new(self.cols.clone());
+        for i in self.obstacles.iter_ones() {
+            let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+            let (tx, ty) = symmetry.apply(self.cols, self.rows, &x, &y);
+            board.toggle_obstacle(&tx, &ty)?;
+        }
 
-        for c in self.get_cells() {
-            match c.get_content() {
-                CellContent::Queen => {
-                    let (x, y, _) = c.get_xyi();
-                    board.toggle_cell(&y, &x)?;
-                }
-                _ => {}
-            }
+        for i in self.occupancy.iter_ones() {
+            let (x, y) = (i % self.cols + 1, i / self.cols + 1);
+            let (tx, ty) = symmetry.apply(self.cols, self.rows, &x, &y);
+            board.toggle_piece(&tx, &ty, self.get_kind(i))?;
         }
 
         Ok(board)
     }
 
-    pub fn get_cols(&self) -> &usize {
-        &self.cols
+    pub fn rot90(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::Rotate90)
     }
 
-    pub fn get_cells(&self) -> &Vec<Cell> {
-        &self.cells
+    pub fn rot180(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::Rotate180)
     }
 
-    pub fn get_available_cells(&self) -> Vec<Cell> {
-        self.cells.iter().fold(vec![], |mut cells, c| {
-            if let CellContent::Empty = c.get_content() {
-                cells.push(c.clone());
-            }
+    pub fn rot270(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::Rotate270)
+    }
 
-            cells
-        })
+    pub fn reflect_diagonal(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::ReflectDiagonal)
     }
 
-    pub fn get_signature(&self) -> &Vec<u8> {
-        &self.signature
+    pub fn reflect_vertical(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::ReflectVertical)
     }
 
-    pub fn to_relative_coord(&self, x: &usize, y: &usize) -> (i32, i32) {
-        let (fx, fy) = (*x as f64, *y as f64);
-        let center = (self.cols as f64) / 2.0 + 0.5;
+    pub fn reflect_anti_diagonal(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::ReflectAntiDiagonal)
+    }
 
-        let mut rx = ((fx - center).abs() + 0.5) as i32;
-        let mut ry = ((fy - center).abs() + 0.5) as i32;
+    pub fn reflect_horizontal(&self) -> Result<Board, String> {
+        self.transform(&Symmetry::ReflectHorizontal)
+    }
 
-        if fx < center {
-            rx = 0 - rx;
-        }
+    /// Build a board from the column of the queen of each row
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let board = Board::from_permutation(&[2, 4, 1, 3]).unwrap();
+    ///
+    /// assert!(board.is_solved());
+    /// assert_eq!(board.to_permutation(), Some(vec![2, 4, 1, 3]));
+    /// ```
+    pub fn from_permutation(permutation: &[usize]) -> Result<Board, String> {
+        let mut board = Board::new(permutation.len());
 
-        if fy < center {
-            ry = 0 - ry;
+        for (y, x) in permutation.iter().enumerate() {
+            board.toggle_cell(x, &(y + 1))?;
         }
 
-        (rx, ry)
+        Ok(board)
     }
 
-    fn from_relative_coord(&self, x: &i32, y: &i32) -> (usize, usize) {
-        let center = (self.cols / 2) as i32;
-        let even_cols = self.cols % 2 == 0;
-
-        let mut ax = x + center;
-        let mut ay = y + center;
+    /// Signature of the board `from_permutation` builds, without building the board
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let permutation = [2, 4, 1, 3];
+    /// let board = Board::from_permutation(&permutation).unwrap();
+    ///
+    /// assert_eq!(&Board::permutation_signature(&permutation), board.get_signature());
+    /// ```
+    pub fn permutation_signature(permutation: &[usize]) -> Vec<u8> {
+        let cols = permutation.len();
+        let mut signature = vec![0_u8; (cols * cols).div_ceil(8)];
 
-        if !even_cols || x < &0 {
-            ax += 1;
+        for (y, x) in permutation.iter().enumerate() {
+            let (byte, mask) = BitBoard::signature_bit(x - 1 + cols * y);
+            signature[byte] |= mask;
         }
 
-        if !even_cols || y < &0 {
-            ay += 1;
+        signature
+    }
+
+    /// Column of the queen of each row, if every row holds exactly one queen
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let mut board = Board::new(3);
+    /// board.toggle_obstacle(&2, &1).unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// board.toggle_cell(&3, &1).unwrap();
+    /// board.toggle_cell(&2, &3).unwrap();
+    ///
+    /// // Three queens, but the second row is empty
+    /// assert_eq!(board.to_permutation(), None);
+    /// ```
+    pub fn to_permutation(&self) -> Option<Vec<usize>> {
+        let mut columns = vec![None; self.rows];
+
+        for (x, y) in self.get_queens() {
+            if columns[y - 1].replace(x).is_some() {
+                return None;
+            }
         }
 
-        (ax as usize, ay as usize)
+        columns.into_iter().collect()
     }
 
-    fn rotate_counterclockwise(&self, x: &usize, y: &usize) -> (usize, usize) {
-        let (rx, ry) = self.to_relative_coord(x, y);
-        let fx = -ry;
-        let fy = rx;
-        let (nx, ny) = self.from_relative_coord(&fx, &fy);
-        (nx, ny)
+    /// Build a board from its bits, as printed by `to_string`
+    ///
+    /// Queens are `1`, holes are `x` and obstacles are `p`. Extra trailing bits, such as the
+    /// padding of a signature, are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    ///
+    /// let board = Board::from_bits(3, 2, "1x0001").unwrap();
+    ///
+    /// assert_eq!(board.get_queens(), vec![(1, 1), (3, 2)]);
+    /// assert_eq!(board.to_string(), "1x0001");
+    /// ```
+    pub fn from_bits(cols: usize, rows: usize, bits: &str) -> Result<Board, String> {
+        let mut board = Board::new_rectangle(cols, rows);
+
+        for (i, c) in bits.chars().take(cols * rows).enumerate() {
+            let (x, y) = (i % cols + 1, i / cols + 1);
+            match c {
+                '0' => {}
+                '1' => board.toggle_cell(&x, &y)?,
+                'x' => board.toggle_hole(&x, &y)?,
+                'p' => board.toggle_obstacle(&x, &y)?,
+                _ => return Err(format!("Invalid board character '{}'", c)),
+            }
+        }
+
+        Ok(board)
     }
 
-    /// Return the permutations of clockwise rotations
+    /// Build a board from a signature and the signature of its holes
     ///
     /// # Example
     ///
     /// ```
     /// use andaluz_core::board::Board;
-    /// use andaluz_core::cell::CellContent;
-    ///
-    /// let mut board = Board::new(8);
     ///
+    /// let mut board = Board::new(5);
+    /// board.toggle_hole(&3, &3).unwrap();
     /// board.toggle_cell(&1, &2).unwrap();
-    /// board.toggle_cell(&2, &5).unwrap();
-    /// board.toggle_cell(&3, &7).unwrap();
-    /// board.toggle_cell(&4, &4).unwrap();
-    /// board.toggle_cell(&5, &1).unwrap();
-    /// board.toggle_cell(&6, &8).unwrap();
-    /// board.toggle_cell(&7, &6).unwrap();
-    /// board.toggle_cell(&8, &3).unwrap();
     ///
-    /// let rs = board.get_equivalent_signatures().unwrap();
+    /// let copy = Board::from_signatures(
+    ///     5,
+    ///     5,
+    ///     board.get_signature(),
+    ///     &board.get_hole_signature(),
+    /// )
+    /// .unwrap();
     ///
-    /// assert_eq!(rs[0], vec![8, 128, 1, 16, 64, 2, 32, 4]);
-    /// assert_eq!(rs[1], vec![2, 16, 64, 8, 1, 128, 32, 4]);
-    /// assert_eq!(rs[2], vec![32, 4, 64, 2, 8, 128, 1, 16]);
-    /// assert_eq!(rs[3], vec![32, 4, 1, 128, 16, 2, 8, 64]);
-    /// assert_eq!(rs[4], vec![64, 8, 2, 16, 128, 1, 4, 32]);
-    /// assert_eq!(rs[5], vec![16, 1, 128, 8, 2, 64, 4, 32]);
-    /// assert_eq!(rs[6], vec![4, 32, 128, 1, 8, 64, 16, 2]);
-    /// assert_eq!(rs[7], vec![4, 32, 2, 64, 16, 1, 128, 8]);
+    /// assert_eq!(copy.to_string(), board.to_string());
     /// ```
-    pub fn get_equivalent_signatures(&mut self) -> Result<Vec<Vec<u8>>, String> {
-        let mut signatures = self.get_rotated_signatures()?;
-
-        let mut mirror_board = self.mirror()?;
-        let mut mirror_signatures = mirror_board.get_rotated_signatures()?;
-
-        signatures.append(&mut mirror_signatures);
+    pub fn from_signatures(
+        cols: usize,
+        rows: usize,
+        signature: &[u8],
+        holes: &[u8],
+    ) -> Result<Board, String> {
+        let queens = BitBoard::from_signature(cols * rows, signature);
+        let holes = BitBoard::from_signature(cols * rows, holes);
+
+        let bits = (0..cols * rows)
+            .map(|i| match (queens.get(i), holes.get(i)) {
+                (true, true) => Err("Cell can't be both a queen and a hole".to_string()),
+                (true, false) => Ok('1'),
+                (false, true) => Ok('x'),
+                (false, false) => Ok('0'),
+            })
+            .collect::<Result<String, String>>()?;
 
-        Ok(signatures)
+        Board::from_bits(cols, rows, &bits)
     }
 
-    fn get_rotated_signatures(&mut self) -> Result<Vec<Vec<u8>>, String> {
-        let rotated_signatures = match &mut self.rotated_signatures {
-            Some(r) => r.clone(),
-            None => {
-                let mut v = vec![];
-
-                v.push(self.get_signature().clone());
-
-                let mut fake_board = Board::new(self.cols.clone());
-                let mut fake_queens = vec![];
-
-                self.get_cells().iter().for_each(|c| match c.get_content() {
-                    CellContent::Queen => {
-                        let (x, y, _) = c.get_xyi();
-                        fake_queens.push((*x, *y));
-                    }
-                    _ => {}
-                });
-
-                // Rotate 3 times
-                for _ in 0..3 {
-                    fake_board.reset();
-                    fake_queens = fake_queens
-                        .iter()
-                        .map(|(x, y)| fake_board.rotate_counterclockwise(&x, &y))
-                        .collect::<Vec<(usize, usize)>>();
-
-                    for (x, y) in &fake_queens {
-                        fake_board.toggle_cell(x, y)?;
-                    }
-
-                    v.push(fake_board.get_signature().clone());
-                }
-
-                v
-            }
-        };
-
-        if self.rotated_signatures.is_none() {
-            self.rotated_signatures = Some(rotated_signatures.clone());
+    pub fn is_solved(&self) -> bool {
+        if !self.mixture.is_empty() {
+            return self.mixture.iter().enumerate().all(|(kind, (_, count))| {
+                self.kinds.values().filter(|k| **k == kind).count() == *count
+            });
         }
 
-        Ok(rotated_signatures)
-    }
-
-    pub fn is_solved(&self) -> bool {
-        let queens = self
-            .get_cells()
-            .iter()
-            .map(|c| match c.get_content() {
-                CellContent::Queen => 1,
-                _ => 0,
-            })
-            .sum::<usize>();
-        queens == self.cols
+        self.occupancy.count_ones() == self.get_goal()
     }
 
     pub fn to_multiline_string(&self) -> String {
@@ -393,7 +1698,7 @@ impl Board {
 
         let mut v = vec![];
 
-        while board.len() > 0 {
+        while !board.is_empty() {
             let rest = board.split_off(*self.get_cols());
             v.push(board);
             board = rest;
@@ -405,14 +1710,22 @@ impl Board {
     }
 }
 
-impl ToString for Board {
-    fn to_string(&self) -> String {
-        let mut board = String::from("");
-        self.get_signature()
-            .iter()
-            .for_each(|s| board = format!("{}{:08b}", board, s));
-        board.split_off(self.get_cols() * self.get_cols());
-        board
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let board = (0..self.cols * self.rows)
+            .map(|i| {
+                if self.occupancy.get(i) {
+                    '1'
+                } else if self.holes.get(i) {
+                    'x'
+                } else if self.obstacles.get(i) {
+                    'p'
+                } else {
+                    '0'
+                }
+            })
+            .collect::<String>();
+        write!(f, "{}", board)
     }
 }
 
@@ -430,18 +1743,8 @@ impl From<Vec<u8>> for Board {
 
 impl From<String> for Board {
     fn from(string: String) -> Self {
-        let cols = (((string.len()) as f64).sqrt()) as usize;
-        let mut board = Board::new(cols);
-        let cells = board.get_cells().clone();
-
-        for (i, c) in string.as_str().char_indices() {
-            if c == '1' {
-                let (x, y, _) = cells[i].get_xyi();
-                board.toggle_cell(x, y).expect("Invalid board provided!");
-            }
-        }
-
-        board
+        let cols = ((string.len()) as f64).sqrt() as usize;
+        Board::from_bits(cols, cols, &string).expect("Invalid board provided!")
     }
 }
 
@@ -449,6 +1752,22 @@ impl From<String> for Board {
 mod tests {
     use super::*;
 
+    #[test]
+    fn boards_are_send() {
+        fn assert_send<T: Send>(_: T) {}
+        assert_send(Board::new(8));
+
+        // Big boards walk their lines instead of sharing masks, so they only hold their cells
+        let mut board = Board::new(1000);
+        board.toggle_cell(&500, &500).unwrap();
+        assert_eq!(board.get_cell_attacks(&1, &1).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&999, &1).unwrap(), &1);
+        assert_eq!(
+            board.get_cell_content(&1000, &1).unwrap(),
+            &CellContent::Empty
+        );
+    }
+
     #[test]
     fn single_move() {
         let mut board: Board = Board::new(8);
@@ -535,4 +1854,292 @@ mod tests {
 
         assert_eq!(board.get_signature(), &signature);
     }
+
+    #[test]
+    fn available_cells_match_contents() {
+        let mut board = Board::new(9);
+
+        board.toggle_cell(&2, &3).unwrap();
+        board.toggle_cell(&7, &4).unwrap();
+        board.toggle_cell(&4, &8).unwrap();
+
+        let available = board
+            .get_available_cells()
+            .iter()
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+        let empty = board
+            .get_cells()
+            .iter()
+            .filter(|c| c.is_empty())
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+
+        assert_eq!(available, empty);
+        assert_eq!(board.get_cell_attacks(&4, &4).unwrap(), &2);
+        assert_eq!(board.get_queens(), vec![(2, 3), (7, 4), (4, 8)]);
+    }
+
+    #[test]
+    fn rectangle_attacks() {
+        let mut board = Board::new_rectangle(6, 3);
+
+        board.toggle_cell(&5, &2).unwrap();
+
+        assert_eq!(board.get_cell_attacks(&1, &2).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&5, &3).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&6, &1).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&4, &3).unwrap(), &1);
+        assert_eq!(board.get_cell_content(&1, &1).unwrap(), &CellContent::Empty);
+        assert!(board.get_cell(&4, &4).is_err());
+        assert_eq!(board.get_available_cells().len(), 18 - 12);
+
+        let board = Board::from_bits(6, 3, &board.to_string()).unwrap();
+        assert_eq!(board.get_queens(), vec![(5, 2)]);
+    }
+
+    #[test]
+    fn holes_round_trip() {
+        let mut board = Board::new(7);
+
+        board.toggle_hole(&4, &4).unwrap();
+        board.toggle_hole(&1, &7).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_hole(&5, &5).unwrap();
+
+        assert_eq!(
+            board.get_cell_content(&5, &5).unwrap(),
+            &CellContent::Blocked
+        );
+        assert!(board
+            .get_available_cells()
+            .iter()
+            .all(|c| !board.get_holes().get(*c.get_xyi().2)));
+
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_cell(&2, &4).unwrap();
+
+        let from_string = Board::from(board.to_string());
+        assert_eq!(from_string.to_string(), board.to_string());
+
+        let from_signatures =
+            Board::from_signatures(7, 7, board.get_signature(), &board.get_hole_signature())
+                .unwrap();
+        assert_eq!(from_signatures.to_string(), board.to_string());
+        assert_eq!(from_signatures.get_cell_attacks(&3, &4).unwrap(), &1);
+    }
+
+    #[test]
+    fn holes_break_symmetry() {
+        let mut board = Board::new(6);
+        assert_eq!(board.get_symmetries().len(), 8);
+
+        board.toggle_hole(&1, &1).unwrap();
+        assert_eq!(
+            board.get_symmetries(),
+            vec![Symmetry::Identity, Symmetry::ReflectDiagonal]
+        );
+
+        let rotated = board.rot90().unwrap();
+        assert_eq!(
+            rotated.get_cell_content(&6, &1).unwrap(),
+            &CellContent::Blocked
+        );
+    }
+
+    #[test]
+    fn obstacles_stop_attacks() {
+        let mut board = Board::new(4);
+        board.toggle_obstacle(&2, &2).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+
+        // The diagonal ends at the obstacle, the row and column run through
+        assert_eq!(board.get_cell_content(&3, &3).unwrap(), &CellContent::Empty);
+        assert_eq!(
+            board.get_cell_content(&4, &1).unwrap(),
+            &CellContent::Attack(1)
+        );
+        assert_eq!(
+            board.get_cell_content(&2, &2).unwrap(),
+            &CellContent::Obstacle
+        );
+        assert!(board.toggle_cell(&2, &2).is_err());
+
+        board.toggle_cell(&3, &3).unwrap();
+        assert_eq!(
+            board.get_cell_content(&3, &1).unwrap(),
+            &CellContent::Attack(2)
+        );
+        assert_eq!(board.get_available_cells().len(), 0);
+
+        assert!(board.toggle_obstacle(&3, &3).is_err());
+        board.toggle_cell(&3, &3).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_obstacle(&2, &2).unwrap();
+        assert!(!board.has_obstacles());
+        assert_eq!(board.get_available_cells().len(), 16);
+    }
+
+    #[test]
+    fn attacked_placements() {
+        let mut board = Board::new(4);
+        board.set_attacked_placements(true).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_cell(&4, &4).unwrap();
+        assert!(board.set_attacked_placements(false).is_err());
+
+        // Holes and obstacles keep the attacks of the pieces counted
+        board.toggle_hole(&2, &2).unwrap();
+        board.toggle_hole(&2, &2).unwrap();
+        assert_eq!(board.get_cell_attacks(&2, &2).unwrap(), &2);
+        board.toggle_obstacle(&3, &3).unwrap();
+        assert_eq!(board.get_cell_attacks(&2, &2).unwrap(), &1);
+        assert_eq!(board.get_cell_content(&4, &4).unwrap(), &CellContent::Queen);
+
+        let reflected = board.reflect_vertical().unwrap();
+        assert!(reflected.allows_attacked_placements());
+        assert_eq!(reflected.get_queens().len(), 2);
+
+        board.reset();
+        board.set_mixture(&[]).unwrap();
+        assert!(!board.allows_attacked_placements());
+
+        // Cells count up to 255 attacks
+        let mut board = Board::new(300);
+        board.set_attacked_placements(true).unwrap();
+        for x in 1..=255 {
+            board.toggle_cell(&x, &1).unwrap();
+        }
+        assert!(board.toggle_cell(&256, &1).is_err());
+        assert_eq!(board.get_cell_attacks(&300, &1).unwrap(), &255);
+        assert_eq!(board.get_queens().len(), 255);
+    }
+
+    #[test]
+    fn superqueens() {
+        // Ten is the smallest board holding one superqueen per row
+        let mut board = Board::new(10);
+        board.set_piece(Piece::superqueen()).unwrap();
+
+        for (y, x) in [3, 6, 9, 1, 4, 7, 10, 2, 5, 8].iter().enumerate() {
+            board.toggle_cell(x, &(y + 1)).unwrap();
+        }
+        assert!(board.is_solved());
+        assert!(board.get_available_cells().is_empty());
+
+        let mut board = Board::from_permutation(&[2, 4, 1, 3]).unwrap();
+        assert!(board.set_piece(Piece::superqueen()).is_err());
+        assert_eq!(board.get_piece(), &Piece::queen());
+        assert_eq!(board.get_attack_mask(0).count_ones(), 9);
+    }
+
+    #[test]
+    fn topologies() {
+        use crate::solver::Solver;
+
+        let mut board = Board::new(5);
+        board.set_topology(Topology::Cylinder).unwrap();
+        board.toggle_cell(&5, &3).unwrap();
+
+        // The diagonals go around the sides but stop at the top and bottom
+        assert_eq!(board.get_cell_attacks(&1, &4).unwrap(), &1);
+        assert_eq!(board.get_cell_content(&4, &5).unwrap(), &CellContent::Empty);
+        assert_eq!(board.get_symmetries().len(), 4);
+        assert!(board.rot90().is_err());
+
+        let rotated = board.rot180().unwrap();
+        assert_eq!(rotated.get_topology(), &Topology::Cylinder);
+        assert_eq!(rotated.get_queens(), vec![(1, 3)]);
+
+        let mut board = Board::new(5);
+        board.set_topology(Topology::KleinBottle).unwrap();
+        board.toggle_cell(&2, &5).unwrap();
+
+        // Crossing the top edge mirrors the column
+        assert_eq!(board.get_cell_attacks(&4, &1).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&3, &1).unwrap(), &1);
+        assert_eq!(board.get_cell_attacks(&2, &2).unwrap(), &1);
+
+        for (cols, solvable) in [(4, false), (5, true), (6, false), (7, true)].iter() {
+            let mut board = Board::new(*cols);
+            board.set_topology(Topology::Torus).unwrap();
+            assert_eq!(board.get_symmetries().len(), 8);
+            assert_eq!(board.get_piece_bound(), *cols);
+
+            let mut solver = Solver::new();
+            assert_eq!(
+                solver.solve(&mut board).unwrap().is_solved(),
+                *solvable,
+                "{} columns",
+                cols
+            );
+        }
+
+        let mut board = Board::from_permutation(&[2, 4, 1, 3]).unwrap();
+        assert!(board.set_topology(Topology::Torus).is_err());
+        assert_eq!(board.get_topology(), &Topology::Plane);
+    }
+
+    #[test]
+    fn obstacles_round_trip() {
+        let mut board = Board::new(3);
+        board.toggle_obstacle(&2, &2).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        board.set_goal(1);
+
+        assert!(board.is_solved());
+        assert_eq!(board.get_symmetries().len(), 8);
+
+        let parsed = Board::from_bits(3, 3, &board.to_string()).unwrap();
+        assert_eq!(parsed.get_signature(), board.get_signature());
+        assert_eq!(
+            parsed.get_obstacle_signature(),
+            board.get_obstacle_signature()
+        );
+    }
+
+    #[test]
+    fn mixed_pieces_keep_their_kind() {
+        let mixture = [(Piece::rook(), 1), (Piece::knight(), 1)];
+        let mut board = Board::new(4);
+        board.set_mixture(&mixture).unwrap();
+        board.toggle_piece(&1, &1, 0).unwrap();
+        board.toggle_piece(&2, &4, 1).unwrap();
+        assert!(board.is_solved());
+
+        // Cells attacked by both kinds count both attacks once unblocked
+        board.toggle_hole(&1, &2).unwrap();
+        board.toggle_hole(&1, &2).unwrap();
+        assert_eq!(board.get_cell_attacks(&1, &2).unwrap(), &2);
+
+        let rotated = board.transform(&Symmetry::Rotate90).unwrap();
+        let placement = rotated
+            .get_placement()
+            .iter()
+            .map(|(x, y, p)| (*x, *y, p.get_name().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placement,
+            vec![(4, 1, "Rook".to_string()), (1, 2, "Knight".to_string())]
+        );
+        assert_eq!(board.get_equivalent_signatures().unwrap().len(), 8);
+        assert!(board
+            .get_equivalent_signatures()
+            .unwrap()
+            .contains(rotated.get_signature()));
+
+        // Swapping the kinds gives another board
+        let mut swapped = Board::new(4);
+        swapped.set_mixture(&mixture).unwrap();
+        swapped.toggle_piece(&1, &1, 1).unwrap();
+        swapped.toggle_piece(&2, &4, 0).unwrap();
+        assert_eq!(swapped.get_occupancy(), board.get_occupancy());
+        assert_ne!(
+            swapped.get_canonical_signature().unwrap(),
+            board.get_canonical_signature().unwrap()
+        );
+
+        assert!(board.toggle_piece(&1, &1, 1).is_err());
+        assert!(board.set_mixture(&[]).is_err());
+    }
 }
@@ -0,0 +1,397 @@
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::cell::{Cell, CellContent};
+use std::fmt;
+
+/// Largest number of dimensions of a `BoardND`
+///
+/// An empty cell is crossed by `(3^d - 1) / 2` lines, each attacking it at most once, and the
+/// count must fit the `u8` of `CellContent::Attack`.
+pub const MAX_DIMENSIONS: usize = 5;
+
+/// Largest number of cells of a `BoardND`, every cell being stored
+pub const MAX_CELLS: usize = 1 << 20;
+
+/// Board with any number of dimensions, where queens attack along every line
+///
+/// A line goes through a cell in each direction of `{-1, 0, 1}^d` except the null one, so a
+/// queen of a cube attacks along 13 lines. Coordinates start at 1, and cells are ordered with
+/// the first coordinate changing fastest. The first two coordinates of each cell are kept as
+/// its `x` and `y`, so the layers print like a `Board`.
+#[derive(Debug, Clone)]
+pub struct BoardND {
+    dimensions: Vec<usize>,
+    strides: Vec<usize>,
+    directions: Vec<Vec<i64>>,
+    cells: Vec<Cell>,
+    occupancy: BitBoard,
+    goal: Option<usize>,
+    signature: Vec<u8>,
+}
+
+impl BoardND {
+    /// Constructor of a hypercube with `side` cells along each of the `dimensions`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    ///
+    /// let mut board = BoardND::new(3, 3).unwrap();
+    ///
+    /// board.toggle_cell(&[2, 2, 2]).unwrap();
+    ///
+    /// assert_eq!(board.get_cells().len(), 27);
+    /// assert!(board.get_available_cells().is_empty());
+    /// assert_eq!(board.get_goal(), 9);
+    /// ```
+    pub fn new(side: usize, dimensions: usize) -> Result<Self, String> {
+        BoardND::from_dimensions(&vec![side; dimensions])
+    }
+
+    /// Constructor of a box with the given number of cells along each dimension
+    ///
+    /// Fails beyond `MAX_DIMENSIONS` dimensions or `MAX_CELLS` cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    ///
+    /// assert_eq!(BoardND::from_dimensions(&[4, 3, 2]).unwrap().get_cells().len(), 24);
+    /// assert!(BoardND::new(1000, 3).is_err());
+    /// assert!(BoardND::new(100000, 5).is_err());
+    /// ```
+    pub fn from_dimensions(dimensions: &[usize]) -> Result<Self, String> {
+        if dimensions.is_empty() || dimensions.len() > MAX_DIMENSIONS {
+            return Err(format!(
+                "Boards must have between 1 and {} dimensions",
+                MAX_DIMENSIONS
+            ));
+        }
+
+        let len = dimensions
+            .iter()
+            .try_fold(1_usize, |len, side| len.checked_mul(*side))
+            .filter(|len| *len <= MAX_CELLS)
+            .ok_or_else(|| format!("Boards can't have more than {} cells", MAX_CELLS))?;
+
+        let mut strides = vec![1; dimensions.len()];
+        for k in 1..dimensions.len() {
+            strides[k] = strides[k - 1] * dimensions[k - 1];
+        }
+
+        // One direction per line: the first non-zero component is positive
+        let mut directions = vec![vec![]];
+        for _ in dimensions {
+            directions = directions
+                .into_iter()
+                .flat_map(|d: Vec<i64>| {
+                    (-1..=1).map(move |v| {
+                        let mut next = d.clone();
+                        next.push(v);
+                        next
+                    })
+                })
+                .collect();
+        }
+        directions.retain(|d| d.iter().find(|v| **v != 0) == Some(&1));
+
+        let mut board = BoardND {
+            dimensions: dimensions.to_vec(),
+            strides,
+            directions,
+            cells: Vec::with_capacity(len),
+            occupancy: BitBoard::new(len),
+            goal: None,
+            signature: vec![],
+        };
+        board.reset();
+
+        Ok(board)
+    }
+
+    /// Remove every queen
+    pub fn reset(&mut self) {
+        let len = self.occupancy.len();
+        self.cells = (0..len)
+            .map(|i| {
+                let coords = self.get_coords(i);
+                Cell::new(coords[0], *coords.get(1).unwrap_or(&1), i)
+            })
+            .collect();
+        self.occupancy = BitBoard::new(len);
+        self.signature = self.occupancy.to_signature();
+    }
+
+    pub fn get_dimensions(&self) -> &Vec<usize> {
+        &self.dimensions
+    }
+
+    /// Coordinates of the cell `i`
+    pub fn get_coords(&self, i: usize) -> Vec<usize> {
+        self.dimensions
+            .iter()
+            .zip(self.strides.iter())
+            .map(|(d, s)| i / s % d + 1)
+            .collect()
+    }
+
+    fn get_i_from_coords(&self, coords: &[usize]) -> Result<usize, String> {
+        if coords.len() != self.dimensions.len()
+            || coords
+                .iter()
+                .zip(self.dimensions.iter())
+                .any(|(c, d)| *c < 1 || c > d)
+        {
+            return Err(format!("Coordinates {:?} out of bounds", coords));
+        }
+
+        Ok(coords
+            .iter()
+            .zip(self.strides.iter())
+            .map(|(c, s)| (c - 1) * s)
+            .sum())
+    }
+
+    pub fn get_cell(&self, coords: &[usize]) -> Result<&Cell, String> {
+        Ok(&self.cells[self.get_i_from_coords(coords)?])
+    }
+
+    pub fn get_cell_content(&self, coords: &[usize]) -> Result<&CellContent, String> {
+        Ok(self.get_cell(coords)?.get_content())
+    }
+
+    /// Put or remove a queen from a cell
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    /// use andaluz_core::cell::CellContent;
+    ///
+    /// let mut board = BoardND::new(4, 3).unwrap();
+    ///
+    /// board.toggle_cell(&[1, 1, 1]).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_content(&[4, 4, 4]).unwrap(), &CellContent::Attack(1));
+    /// assert_eq!(board.get_cell_content(&[2, 3, 1]).unwrap(), &CellContent::Empty);
+    /// assert!(board.toggle_cell(&[1, 3, 3]).is_err());
+    /// ```
+    pub fn toggle_cell(&mut self, coords: &[usize]) -> Result<(), String> {
+        let i = self.get_i_from_coords(coords)?;
+
+        let attack = self.cells[i].toggle()? == &CellContent::Queen;
+        for p in self.get_attack_mask(i).iter_ones() {
+            self.cells[p].attack_or_relieve(&attack)?;
+        }
+
+        self.occupancy.toggle(i);
+        let (byte, mask) = BitBoard::signature_bit(i);
+        self.signature[byte] ^= mask;
+
+        Ok(())
+    }
+
+    /// Cells attacked by a queen on the cell `i`
+    pub fn get_attack_mask(&self, i: usize) -> BitBoard {
+        let mut mask = BitBoard::new(self.cells.len());
+        let origin = self.get_coords(i);
+
+        for direction in &self.directions {
+            for sign in [1, -1].iter() {
+                let mut coords = origin.iter().map(|c| *c as i64).collect::<Vec<i64>>();
+                loop {
+                    coords
+                        .iter_mut()
+                        .zip(direction.iter())
+                        .for_each(|(c, d)| *c += sign * d);
+
+                    let inside = coords
+                        .iter()
+                        .zip(self.dimensions.iter())
+                        .all(|(c, d)| *c >= 1 && *c <= *d as i64);
+                    if !inside {
+                        break;
+                    }
+
+                    let p = coords
+                        .iter()
+                        .zip(self.strides.iter())
+                        .map(|(c, s)| (*c as usize - 1) * s)
+                        .sum();
+                    mask.set(p);
+                }
+            }
+        }
+
+        mask
+    }
+
+    pub fn get_cells(&self) -> &Vec<Cell> {
+        &self.cells
+    }
+
+    /// Coordinates of the placed queens, in cell order
+    pub fn get_queens(&self) -> Vec<Vec<usize>> {
+        self.occupancy
+            .iter_ones()
+            .map(|i| self.get_coords(i))
+            .collect()
+    }
+
+    /// Cells that are neither occupied nor attacked
+    pub fn get_available_cells(&self) -> Vec<Cell> {
+        self.cells
+            .iter()
+            .filter(|c| c.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Number of queens of a complete solution
+    ///
+    /// Defaults to one queen per line along the longest dimension, `n^(d-1)` for a hypercube.
+    pub fn get_goal(&self) -> usize {
+        let longest = self.dimensions.iter().max().cloned().unwrap_or(1);
+        self.goal.unwrap_or(self.cells.len() / longest.max(1))
+    }
+
+    pub fn set_goal(&mut self, goal: usize) {
+        self.goal = Some(goal);
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.occupancy.count_ones() == self.get_goal()
+    }
+
+    /// Queen bits of every cell, packed MSB-first in cell order
+    pub fn get_signature(&self) -> &Vec<u8> {
+        &self.signature
+    }
+
+    /// Build a board from its dimensions and signature
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    ///
+    /// let mut board = BoardND::new(3, 3).unwrap();
+    /// board.toggle_cell(&[1, 2, 3]).unwrap();
+    ///
+    /// let copy = BoardND::from_signature(&[3, 3, 3], board.get_signature()).unwrap();
+    ///
+    /// assert_eq!(copy.get_queens(), vec![vec![1, 2, 3]]);
+    /// ```
+    pub fn from_signature(dimensions: &[usize], signature: &[u8]) -> Result<Self, String> {
+        let mut board = BoardND::from_dimensions(dimensions)?;
+
+        for i in BitBoard::from_signature(board.cells.len(), signature).iter_ones() {
+            let coords = board.get_coords(i);
+            board.toggle_cell(&coords)?;
+        }
+
+        Ok(board)
+    }
+
+    /// Planar slice with the coordinates after the first two fixed to `layer`
+    ///
+    /// Queens of a slice never attack each other, so the slice is a valid `Board`.
+    pub fn get_layer(&self, layer: &[usize]) -> Result<Board, String> {
+        let cols = self.dimensions[0];
+        let rows = *self.dimensions.get(1).unwrap_or(&1);
+
+        let mut board = Board::new_rectangle(cols, rows);
+        for y in 1..=rows {
+            for x in 1..=cols {
+                let mut coords = vec![x];
+                if self.dimensions.len() > 1 {
+                    coords.push(y);
+                }
+                coords.extend_from_slice(layer);
+
+                if self.occupancy.get(self.get_i_from_coords(&coords)?) {
+                    board.toggle_cell(&x, &y)?;
+                }
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Every layer as printed by `Board::to_multiline_string`, each after its coordinates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    ///
+    /// let mut board = BoardND::new(2, 3).unwrap();
+    /// board.toggle_cell(&[1, 2, 1]).unwrap();
+    ///
+    /// assert_eq!(board.to_multiline_string(), "Layer [1]:\n10\n00\n\nLayer [2]:\n00\n00\n");
+    /// ```
+    pub fn to_multiline_string(&self) -> String {
+        let layers = self.dimensions.iter().skip(2).product::<usize>();
+        let planar = self.dimensions.iter().take(2).product::<usize>();
+
+        (0..layers)
+            .map(|l| {
+                let layer = self
+                    .get_coords(l * planar)
+                    .split_off(2.min(self.dimensions.len()));
+                let board = self
+                    .get_layer(&layer)
+                    .expect("Layers of a board are always valid");
+                format!("Layer {:?}:\n{}", layer, board.to_multiline_string())
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for BoardND {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let board = (0..self.cells.len())
+            .map(|i| if self.occupancy.get(i) { '1' } else { '0' })
+            .collect::<String>();
+        write!(f, "{}", board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube_lines() {
+        let board = BoardND::new(5, 3).unwrap();
+        assert_eq!(board.directions.len(), 13);
+
+        // The center of a 5x5x5 cube sees 4 cells along each of its 13 lines
+        assert_eq!(board.get_attack_mask(62).count_ones(), 13 * 4);
+        assert_eq!(board.get_coords(62), vec![3, 3, 3]);
+
+        assert!(BoardND::new(3, MAX_DIMENSIONS + 1).is_err());
+        assert_eq!(BoardND::new(3, 4).unwrap().directions.len(), 40);
+    }
+
+    #[test]
+    fn toggle_round_trip() {
+        let mut board = BoardND::new(4, 3).unwrap();
+        board.toggle_cell(&[1, 2, 3]).unwrap();
+        board.toggle_cell(&[3, 1, 1]).unwrap();
+        assert_eq!(
+            board.get_cell_content(&[2, 2, 2]).unwrap(),
+            &CellContent::Attack(2)
+        );
+
+        board.toggle_cell(&[1, 2, 3]).unwrap();
+        board.toggle_cell(&[3, 1, 1]).unwrap();
+        assert!(board.get_cells().iter().all(|c| c.is_empty()));
+        assert_eq!(board.get_signature(), &vec![0; 8]);
+        assert!(board.toggle_cell(&[5, 1, 1]).is_err());
+        assert!(board.toggle_cell(&[1, 1]).is_err());
+    }
+}
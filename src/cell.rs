@@ -3,6 +3,48 @@ pub enum CellContent {
     Empty,
     Queen,
     Attack(u8),
+    Blocked,
+    Obstacle,
+    /// Attacked by white and black pieces, on a board of peaceable armies
+    Armies(u8, u8),
+}
+
+/// Side of a piece on a board of peaceable armies
+///
+/// Pieces only have to keep away from the other side, and the kind of a piece in the mixture
+/// of the board is its side, white first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub const ALL: [Color; 2] = [Color::White, Color::Black];
+
+    /// Side of the pieces of a kind of the mixture
+    pub fn of_kind(kind: usize) -> Result<Self, String> {
+        match kind {
+            0 => Ok(Color::White),
+            1 => Ok(Color::Black),
+            _ => Err(format!("No side for the kind of piece {}", kind)),
+        }
+    }
+
+    /// Kind of the pieces of this side in the mixture
+    pub fn get_kind(&self) -> usize {
+        match self {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+
+    pub fn opponent(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,26 +74,101 @@ impl Cell {
     }
 
     pub fn is_queen(&self) -> bool {
-        match &self.content {
-            CellContent::Queen => true,
-            _ => false,
-        }
+        matches!(&self.content, CellContent::Queen)
     }
 
     pub fn is_empty(&self) -> bool {
-        match &self.content {
-            CellContent::Empty => true,
-            _ => false,
-        }
+        matches!(&self.content, CellContent::Empty)
     }
 
     pub fn is_attacked(&self) -> bool {
+        matches!(&self.content, CellContent::Attack(_))
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        matches!(&self.content, CellContent::Blocked)
+    }
+
+    pub fn is_obstacle(&self) -> bool {
+        matches!(&self.content, CellContent::Obstacle)
+    }
+
+    /// Whether a piece of the side `color` can go on the cell, away from the other side
+    pub fn is_available_for(&self, color: &Color) -> bool {
+        matches!(
+            (&self.content, color),
+            (CellContent::Empty, _)
+                | (CellContent::Armies(_, 0), Color::White)
+                | (CellContent::Armies(0, _), Color::Black)
+        )
+    }
+
+    /// Put a piece of the side `color` on the cell
+    pub fn occupy(&mut self, color: &Color) -> Result<(), String> {
+        if !self.is_available_for(color) {
+            return Err("Cell not available for this side".to_string());
+        }
+
+        self.content = CellContent::Queen;
+        Ok(())
+    }
+
+    /// Put a piece on the cell even if attacked, its attacks counted again once removed
+    pub fn place(&mut self) -> Result<(), String> {
         match &self.content {
-            CellContent::Attack(_) => true,
-            _ => false,
+            CellContent::Empty | CellContent::Attack(_) => {
+                self.content = CellContent::Queen;
+                Ok(())
+            }
+            _ => Err("Cell not available for a piece".to_string()),
         }
     }
 
+    /// Count one more attack by the side `color`, failing past 255 attacks by one side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::cell::{Cell, CellContent, Color};
+    ///
+    /// let mut cell = Cell::new(1, 1, 0);
+    /// (0..255).for_each(|_| cell.attack_by(&Color::White).unwrap());
+    ///
+    /// assert!(cell.attack_by(&Color::White).is_err());
+    /// assert!(cell.attack_by(&Color::Black).is_ok());
+    /// assert_eq!(cell.get_content(), &CellContent::Armies(255, 1));
+    /// ```
+    pub fn attack_by(&mut self, color: &Color) -> Result<(), String> {
+        let (white, black) = match &self.content {
+            CellContent::Empty => (0, 0_u8),
+            CellContent::Armies(white, black) => (*white, *black),
+            _ => return Err("Cell not available for attack".to_string()),
+        };
+
+        let too_many = || "Too many attacks on the cell".to_string();
+        self.content = match color {
+            Color::White => CellContent::Armies(white.checked_add(1).ok_or_else(too_many)?, black),
+            Color::Black => CellContent::Armies(white, black.checked_add(1).ok_or_else(too_many)?),
+        };
+        Ok(())
+    }
+
+    /// Count one less attack by the side `color`
+    pub fn relieve_by(&mut self, color: &Color) -> Result<(), String> {
+        self.content = match (&self.content, color) {
+            (CellContent::Armies(1, 0), Color::White)
+            | (CellContent::Armies(0, 1), Color::Black) => CellContent::Empty,
+            (CellContent::Armies(white, black), Color::White) if *white > 0 => {
+                CellContent::Armies(white - 1, *black)
+            }
+            (CellContent::Armies(white, black), Color::Black) if *black > 0 => {
+                CellContent::Armies(*white, black - 1)
+            }
+            _ => return Err("Cell not available for relieve".to_string()),
+        };
+        Ok(())
+    }
+
     pub fn toggle(&mut self) -> Result<&CellContent, String> {
         match &self.content {
             CellContent::Empty => {
@@ -62,10 +179,50 @@ impl Cell {
                 self.content = CellContent::Empty;
                 Ok(&self.content)
             }
+            CellContent::Blocked => Err("Blocked cell not avaliable".to_string()),
+            CellContent::Obstacle => Err("Obstacle cell not avaliable".to_string()),
             _ => Err("Attacked cell not avaliable".to_string()),
         }
     }
 
+    /// Turn the cell into a hole, discarding the attacks it receives
+    pub fn block(&mut self) -> Result<(), String> {
+        match &self.content {
+            CellContent::Queen => Err("Cell occupied by a queen".to_string()),
+            CellContent::Obstacle => Err("Cell occupied by an obstacle".to_string()),
+            _ => {
+                self.content = CellContent::Blocked;
+                Ok(())
+            }
+        }
+    }
+
+    /// Put an obstacle on an empty cell
+    pub fn obstruct(&mut self) -> Result<(), String> {
+        match &self.content {
+            CellContent::Empty => {
+                self.content = CellContent::Obstacle;
+                Ok(())
+            }
+            _ => Err("Cell not available for an obstacle".to_string()),
+        }
+    }
+
+    /// Restore a hole, attacked by `attacks` queens
+    pub fn unblock(&mut self, attacks: u8) -> Result<(), String> {
+        match &self.content {
+            CellContent::Blocked if attacks == 0 => {
+                self.content = CellContent::Empty;
+                Ok(())
+            }
+            CellContent::Blocked => {
+                self.content = CellContent::Attack(attacks);
+                Ok(())
+            }
+            _ => Err("Cell not blocked".to_string()),
+        }
+    }
+
     pub fn attack(&mut self) -> Result<(), String> {
         match &self.content {
             CellContent::Empty => {
@@ -73,7 +230,10 @@ impl Cell {
                 Ok(())
             }
             CellContent::Attack(a) => {
-                self.content = CellContent::Attack(a + 1);
+                let attacks = a
+                    .checked_add(1)
+                    .ok_or_else(|| "Too many attacks on the cell".to_string())?;
+                self.content = CellContent::Attack(attacks);
                 Ok(())
             }
             _ => Err("Cell not available for attack".to_string()),
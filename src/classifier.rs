@@ -21,7 +21,7 @@ impl<'a> Classifier<'a> {
         self.heuristics.is_empty()
     }
 
-    pub fn get_heuristics(&self) -> &Vec<Heuristic> {
+    pub fn get_heuristics(&self) -> &Vec<Heuristic<'a>> {
         &self.heuristics
     }
 
@@ -42,6 +42,12 @@ impl<'a> Classifier<'a> {
     }
 }
 
+impl<'a> Default for Classifier<'a> {
+    fn default() -> Self {
+        Classifier::new()
+    }
+}
+
 impl<'a> fmt::Display for Classifier<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.heuristics)
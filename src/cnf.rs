@@ -0,0 +1,433 @@
+use crate::board::Board;
+use crate::cell::CellContent;
+use crate::graph::AttackGraph;
+use std::collections::HashSet;
+
+/// Encoding of the at-most-one constraints over a group of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// One clause per pair of cells, no extra variables
+    Pairwise,
+    /// Sinz's sequential counter, one extra variable per cell
+    SequentialCounter,
+    /// Klieber and Kwon's commander variables over groups of three cells
+    Commander,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Result<Encoding, String> {
+        match name {
+            "pairwise" => Ok(Encoding::Pairwise),
+            "sequential" => Ok(Encoding::SequentialCounter),
+            "commander" => Ok(Encoding::Commander),
+            _ => Err(format!("Unknown encoding '{}'", name)),
+        }
+    }
+}
+
+/// Boolean formula in conjunctive normal form, with DIMACS literals
+///
+/// The variable `x + cols * (y - 1)` holds whether a piece is on the cell `(x, y)`, the
+/// variables past the cells are introduced by the encodings of the constraints.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::cnf::{encode, Encoding};
+///
+/// let cnf = encode(&Board::new(2), &Encoding::Pairwise).unwrap();
+///
+/// assert_eq!(cnf.get_variables(), &4);
+/// assert_eq!(
+///     cnf.to_dimacs(),
+///     "c 2x2 Queen, cell (x, y) is variable x + 2 * (y - 1)\n\
+///      p cnf 4 8\n1 2 0\n-1 -2 0\n3 4 0\n-3 -4 0\n-1 -3 0\n-2 -4 0\n-1 -4 0\n-3 -2 0\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cnf {
+    header: String,
+    variables: usize,
+    clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    pub fn get_variables(&self) -> &usize {
+        &self.variables
+    }
+
+    pub fn get_clauses(&self) -> &Vec<Vec<i32>> {
+        &self.clauses
+    }
+
+    /// DIMACS CNF text, as read by SAT solvers
+    pub fn to_dimacs(&self) -> String {
+        let mut dimacs = format!(
+            "c {}\np cnf {} {}\n",
+            self.header,
+            self.variables,
+            self.clauses.len()
+        );
+        for clause in &self.clauses {
+            for literal in clause {
+                dimacs.push_str(&format!("{} ", literal));
+            }
+            dimacs.push_str("0\n");
+        }
+        dimacs
+    }
+
+    fn new_variable(&mut self) -> i32 {
+        self.variables += 1;
+        self.variables as i32
+    }
+
+    fn at_most_one(&mut self, literals: &[i32], encoding: &Encoding) {
+        match encoding {
+            Encoding::Pairwise => self.pairwise(literals),
+            Encoding::SequentialCounter => self.sequential_counter(literals),
+            Encoding::Commander => self.commander(literals),
+        }
+    }
+
+    fn pairwise(&mut self, literals: &[i32]) {
+        for (k, a) in literals.iter().enumerate() {
+            for b in &literals[k + 1..] {
+                self.clauses.push(vec![-a, -b]);
+            }
+        }
+    }
+
+    /// The counter `s[k]` is true once any of the first `k + 1` literals is
+    fn sequential_counter(&mut self, literals: &[i32]) {
+        let n = literals.len();
+        if n < 2 {
+            return;
+        }
+
+        let counters = (0..n - 1)
+            .map(|_| self.new_variable())
+            .collect::<Vec<i32>>();
+
+        self.clauses.push(vec![-literals[0], counters[0]]);
+        for k in 1..n - 1 {
+            self.clauses.push(vec![-literals[k], counters[k]]);
+            self.clauses.push(vec![-counters[k - 1], counters[k]]);
+            self.clauses.push(vec![-literals[k], -counters[k - 1]]);
+        }
+        self.clauses.push(vec![-literals[n - 1], -counters[n - 2]]);
+    }
+
+    /// Each group of three literals gets a commander, true exactly when one of them is, and
+    /// the commanders are constrained the same way until three are left
+    fn commander(&mut self, literals: &[i32]) {
+        if literals.len() <= 3 {
+            self.pairwise(literals);
+            return;
+        }
+
+        let mut commanders = vec![];
+        for group in literals.chunks(3) {
+            let commander = self.new_variable();
+            self.pairwise(group);
+
+            let mut any = vec![-commander];
+            for literal in group {
+                self.clauses.push(vec![-literal, commander]);
+                any.push(*literal);
+            }
+            self.clauses.push(any);
+            commanders.push(commander);
+        }
+
+        self.commander(&commanders);
+    }
+}
+
+/// Encode the solutions of a board, with its placed pieces, as a formula
+///
+/// The board must hold one piece per row, or per column when there are fewer columns, and
+/// the piece must attack along them. Every row gets an exactly-one constraint, every stretch
+/// of the other lines where the cells attack each other an at-most-one constraint, and the
+/// attacks left out of the lines, such as the leaps of a knight, a clause per pair. Holes
+/// and obstacles are false and the placed pieces true.
+pub fn encode(board: &Board, encoding: &Encoding) -> Result<Cnf, String> {
+    if !board.get_mixture().is_empty() || board.allows_attacked_placements() {
+        return Err("Only boards of a single piece can be encoded".to_string());
+    }
+
+    let (cols, rows) = (*board.get_cols(), *board.get_rows());
+    if board.get_goal() != cols.min(rows) {
+        return Err("Only boards with one piece per row or column can be encoded".to_string());
+    }
+
+    let graph = AttackGraph::from_board(board)?;
+    let edges = graph
+        .get_edges()
+        .iter()
+        .cloned()
+        .collect::<HashSet<(usize, usize)>>();
+    let attacks = |a: usize, b: usize| edges.contains(&(a.min(b), a.max(b)));
+
+    let mut holdable = vec![false; cols * rows];
+    graph
+        .get_vertices()
+        .iter()
+        .for_each(|v| holdable[*v] = true);
+
+    // Lines of each direction, the direction of the pieces per row or column first
+    let directions = if rows <= cols {
+        [(1, 0), (0, 1), (1, 1), (1, -1)]
+    } else {
+        [(0, 1), (1, 0), (1, 1), (1, -1)]
+    };
+
+    let mut cnf = Cnf {
+        header: format!(
+            "{}x{} {}, cell (x, y) is variable x + {} * (y - 1)",
+            cols,
+            rows,
+            board.get_piece(),
+            cols
+        ),
+        variables: cols * rows,
+        clauses: vec![],
+    };
+    let mut covered = HashSet::new();
+
+    for (d, (dx, dy)) in directions.iter().enumerate() {
+        for line in get_lines(cols, rows, *dx, *dy) {
+            let cells = line
+                .into_iter()
+                .filter(|i| holdable[*i])
+                .collect::<Vec<usize>>();
+
+            let mut stretches: Vec<Vec<usize>> = vec![];
+            for i in cells {
+                match stretches.last_mut() {
+                    Some(stretch) if stretch.iter().all(|j| attacks(i, *j)) => stretch.push(i),
+                    _ => stretches.push(vec![i]),
+                }
+            }
+
+            if d == 0 {
+                if stretches.len() > 1 {
+                    return Err("The piece doesn't attack along every row".to_string());
+                }
+                let stretch = stretches.first().cloned().unwrap_or_default();
+                cnf.clauses
+                    .push(stretch.iter().map(|i| *i as i32 + 1).collect());
+            }
+
+            for stretch in stretches.iter().filter(|s| s.len() > 1) {
+                for (k, a) in stretch.iter().enumerate() {
+                    for b in &stretch[k + 1..] {
+                        covered.insert((*a.min(b), *a.max(b)));
+                    }
+                }
+                let literals = stretch.iter().map(|i| *i as i32 + 1).collect::<Vec<i32>>();
+                cnf.at_most_one(&literals, encoding);
+            }
+        }
+    }
+
+    for (a, b) in graph.get_edges() {
+        if !covered.contains(&(*a, *b)) {
+            cnf.clauses.push(vec![-(*a as i32 + 1), -(*b as i32 + 1)]);
+        }
+    }
+
+    for cell in board.get_cells() {
+        let i = *cell.get_xyi().2;
+        if !holdable[i] {
+            cnf.clauses.push(vec![-(i as i32 + 1)]);
+        } else if cell.is_queen() {
+            cnf.clauses.push(vec![i as i32 + 1]);
+        }
+    }
+
+    Ok(cnf)
+}
+
+/// Cells of every line going `(dx, dy)` across the board, without wrapping around
+fn get_lines(cols: usize, rows: usize, dx: i64, dy: i64) -> Vec<Vec<usize>> {
+    let (cols, rows) = (cols as i64, rows as i64);
+    let inside = |x: i64, y: i64| x >= 0 && y >= 0 && x < cols && y < rows;
+
+    let mut lines = vec![];
+    for y in 0..rows {
+        for x in 0..cols {
+            if inside(x - dx, y - dy) {
+                continue;
+            }
+
+            let (mut cx, mut cy) = (x, y);
+            let mut line = vec![];
+            while inside(cx, cy) {
+                line.push((cx + cols * cy) as usize);
+                cx += dx;
+                cy += dy;
+            }
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Board with the pieces of a model of the formula of `encode`
+///
+/// The true cell variables not already holding a piece are placed on a copy of the board.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::cnf::decode;
+///
+/// let board = decode(&Board::new(4), &[-1, 2, -3, -4, -5, -6, -7, 8, 9, 15, -16]).unwrap();
+///
+/// assert_eq!(board.get_queens(), vec![(2, 1), (4, 2), (1, 3), (3, 4)]);
+/// assert!(board.is_solved());
+/// ```
+pub fn decode(board: &Board, model: &[i32]) -> Result<Board, String> {
+    let (cols, rows) = (*board.get_cols(), *board.get_rows());
+    let mut decoded = board.clone();
+
+    for literal in model.iter().filter(|l| **l > 0) {
+        let i = *literal as usize - 1;
+        if i >= cols * rows {
+            continue;
+        }
+
+        let (x, y) = (i % cols + 1, i / cols + 1);
+        if decoded.get_cell_content(&x, &y)? != &CellContent::Queen {
+            decoded
+                .toggle_cell(&x, &y)
+                .map_err(|e| format!("Unable to place ({}, {}): {}", x, y, e))?;
+        }
+    }
+
+    Ok(decoded)
+}
+
+/// Literals of the model printed by a SAT solver
+///
+/// The values are read from the `v` lines of the competition format, or from every line
+/// when there are none, skipping comments and the final `0`. An unsatisfiable answer is an
+/// error.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::cnf::parse_model;
+///
+/// assert_eq!(parse_model("s SATISFIABLE\nv 1 -2\nv 3 0\n").unwrap(), vec![1, -2, 3]);
+/// assert_eq!(parse_model("SAT\n-1 2 0\n").unwrap(), vec![-1, 2]);
+/// assert!(parse_model("s UNSATISFIABLE\n").is_err());
+/// ```
+pub fn parse_model(output: &str) -> Result<Vec<i32>, String> {
+    if output.lines().any(|l| l.trim().contains("UNSAT")) {
+        return Err("The formula is unsatisfiable".to_string());
+    }
+
+    let has_values = output.lines().any(|l| l.starts_with("v "));
+    output
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.starts_with('c') && !l.starts_with('s') && l != &"SAT")
+        .filter(|l| !has_values || l.starts_with("v "))
+        .flat_map(|l| l.trim_start_matches("v ").split_whitespace())
+        .filter(|v| *v != "0")
+        .map(|v| {
+            v.parse::<i32>()
+                .map_err(|_| format!("Invalid literal '{}'", v))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+
+    /// Whether some values of the unset variables satisfy every clause
+    fn satisfiable(clauses: &[Vec<i32>], values: &mut Vec<Option<bool>>) -> bool {
+        let value = |l: &i32, values: &Vec<Option<bool>>| {
+            values[l.unsigned_abs() as usize].map(|v| v == (*l > 0))
+        };
+
+        let open = clauses
+            .iter()
+            .find(|c| !c.iter().any(|l| value(l, values) == Some(true)));
+        let literal = match open {
+            None => return true,
+            Some(clause) => match clause.iter().find(|l| value(l, values).is_none()) {
+                None => return false,
+                Some(l) => *l,
+            },
+        };
+
+        let variable = literal.unsigned_abs() as usize;
+        for choice in [literal > 0, literal < 0] {
+            values[variable] = Some(choice);
+            if satisfiable(clauses, values) {
+                values[variable] = None;
+                return true;
+            }
+        }
+        values[variable] = None;
+        false
+    }
+
+    /// Number of placements of one piece per row that some values of the other variables
+    /// complete into a model
+    fn count_models(board: &Board, encoding: &Encoding) -> usize {
+        let cnf = encode(board, encoding).unwrap();
+        let (cols, rows) = (*board.get_cols(), *board.get_rows());
+
+        (0..cols.pow(rows as u32))
+            .filter(|p| {
+                let mut values = vec![None; cnf.get_variables() + 1];
+                for i in 0..cols * rows {
+                    values[i + 1] = Some(p / cols.pow((i / cols) as u32) % cols == i % cols);
+                }
+                satisfiable(cnf.get_clauses(), &mut values)
+            })
+            .count()
+    }
+
+    #[test]
+    fn encodings_keep_the_solutions() {
+        let encodings = [
+            Encoding::Pairwise,
+            Encoding::SequentialCounter,
+            Encoding::Commander,
+        ];
+
+        for encoding in &encodings {
+            assert_eq!(count_models(&Board::new(4), encoding), 2);
+            assert_eq!(count_models(&Board::new(5), encoding), 10);
+
+            let mut board = Board::new(5);
+            board.toggle_cell(&1, &1).unwrap();
+            assert_eq!(count_models(&board, encoding), 2);
+
+            let mut board = Board::new(4);
+            board.toggle_hole(&2, &1).unwrap();
+            assert_eq!(count_models(&board, encoding), 1);
+
+            let mut board = Board::new(4);
+            board.set_piece(Piece::rook()).unwrap();
+            assert_eq!(count_models(&board, encoding), 24);
+        }
+
+        let mut board = Board::new(4);
+        board.set_piece(Piece::knight()).unwrap();
+        assert!(encode(&board, &Encoding::Pairwise).is_err());
+
+        board.set_piece(Piece::queen()).unwrap();
+        board.set_goal(3);
+        assert!(encode(&board, &Encoding::Pairwise).is_err());
+    }
+}
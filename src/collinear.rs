@@ -0,0 +1,154 @@
+/// Cells lying on a line through two placed queens, for the no-three-in-line problem
+///
+/// Lines of every rational slope are followed with exact integer steps: the line through two
+/// queens `(dx, dy)` apart visits every cell `(dx / g, dy / g)` apart, `g` being the greatest
+/// common divisor of `dx` and `dy`. Placing or removing a queen only updates the lines it makes
+/// with each of the other queens.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::collinear::CollinearCells;
+///
+/// let mut lines = CollinearCells::new(8, 8);
+///
+/// lines.toggle(&1, &1).unwrap();
+/// lines.toggle(&2, &3).unwrap();
+///
+/// assert!(lines.is_blocked(&3, &5));
+/// assert!(lines.is_blocked(&4, &7));
+/// assert!(!lines.is_blocked(&3, &6));
+/// assert!(!lines.is_blocked(&5, &9));
+///
+/// lines.toggle(&1, &1).unwrap();
+///
+/// assert!(!lines.is_blocked(&3, &5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CollinearCells {
+    cols: usize,
+    rows: usize,
+    queens: Vec<(usize, usize)>,
+    lines: Vec<u16>,
+}
+
+impl CollinearCells {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        CollinearCells {
+            cols,
+            rows,
+            queens: vec![],
+            lines: vec![0; cols * rows],
+        }
+    }
+
+    /// Place a queen on the cell `(x, y)`, or remove it
+    ///
+    /// Placing a queen on a blocked cell is allowed, the queens then make three in line.
+    pub fn toggle(&mut self, x: &usize, y: &usize) -> Result<(), String> {
+        if *x < 1 || *y < 1 || *x > self.cols || *y > self.rows {
+            return Err(format!("Coordinates ({}, {}) out of bounds", x, y));
+        }
+
+        let cell = (*x, *y);
+        match self.queens.iter().position(|q| *q == cell) {
+            Some(k) => {
+                self.queens.remove(k);
+                for q in self.queens.clone() {
+                    self.mark(cell, q, false);
+                }
+            }
+            None => {
+                for q in self.queens.clone() {
+                    self.mark(cell, q, true);
+                }
+                self.queens.push(cell);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a queen on the cell `(x, y)` would be in line with two other queens
+    ///
+    /// Cells off the board are never blocked.
+    pub fn is_blocked(&self, x: &usize, y: &usize) -> bool {
+        if *x < 1 || *y < 1 || *x > self.cols || *y > self.rows {
+            return false;
+        }
+
+        self.lines[x - 1 + self.cols * (y - 1)] > 0
+    }
+
+    pub fn get_queens(&self) -> &Vec<(usize, usize)> {
+        &self.queens
+    }
+
+    /// Add or remove the line through two queens from every cell it visits
+    fn mark(&mut self, (ax, ay): (usize, usize), (bx, by): (usize, usize), add: bool) {
+        let (dx, dy) = (bx as i64 - ax as i64, by as i64 - ay as i64);
+        let g = gcd(dx.abs(), dy.abs());
+        let (sx, sy) = (dx / g, dy / g);
+
+        // Walk back to the edge, then along the whole line
+        let (cols, rows) = (self.cols as i64, self.rows as i64);
+        let inside = |x: i64, y: i64| x >= 1 && y >= 1 && x <= cols && y <= rows;
+
+        let (mut x, mut y) = (ax as i64, ay as i64);
+        while inside(x - sx, y - sy) {
+            x -= sx;
+            y -= sy;
+        }
+
+        while inside(x, y) {
+            let i = (x - 1 + cols * (y - 1)) as usize;
+            if add {
+                self.lines[i] += 1;
+            } else {
+                self.lines[i] -= 1;
+            }
+            x += sx;
+            y += sy;
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_of_every_slope() {
+        let mut lines = CollinearCells::new(20, 20);
+        lines.toggle(&2, &1).unwrap();
+        lines.toggle(&5, &8).unwrap();
+
+        // Slope 7/3, exactly the cells 3 columns and 7 rows apart
+        let blocked = (1..=20)
+            .flat_map(|y| (1..=20).map(move |x| (x, y)))
+            .filter(|(x, y)| lines.is_blocked(x, y))
+            .collect::<Vec<(usize, usize)>>();
+        assert_eq!(blocked, vec![(2, 1), (5, 8), (8, 15)]);
+
+        lines.toggle(&5, &8).unwrap();
+        assert!(!lines.is_blocked(&8, &15));
+        assert!(lines.toggle(&21, &1).is_err());
+
+        // Off the board, including the cells the flat index would wrap onto
+        lines.toggle(&1, &2).unwrap();
+        lines.toggle(&2, &2).unwrap();
+        assert!(lines.is_blocked(&20, &2));
+        assert!(!lines.is_blocked(&21, &1));
+        assert!(!lines.is_blocked(&0, &2));
+        assert!(!lines.is_blocked(&1, &0));
+        assert!(!lines.is_blocked(&1, &21));
+    }
+}
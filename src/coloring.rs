@@ -0,0 +1,181 @@
+use crate::bitboard::BitBoard;
+use std::fmt;
+
+/// Coloring of the cells of a board where no two cells of a color attack each other
+///
+/// Each color class is a set of non-attacking pieces, a solution of the board when it holds
+/// one piece per row. Holes and obstacles have no color. The signature holds one block of
+/// bits per color, laid out as the signature of a board with a mixture of pieces.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::coloring::Coloring;
+///
+/// let coloring = Coloring::from_signature(2, 2, &[0x80, 0x40, 0x20, 0x10]).unwrap();
+///
+/// assert_eq!(coloring.get_color_count(), 4);
+/// assert_eq!(coloring.get_color(&2, &1).unwrap(), &Some(1));
+/// assert_eq!(coloring.to_multiline_string(), "23\n01\n");
+/// assert!(!coloring.splits_into_solutions());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coloring {
+    cols: usize,
+    rows: usize,
+    colors: Vec<Option<usize>>,
+}
+
+impl Coloring {
+    /// Coloring of a `cols` by `rows` board from the color of each cell, in cell order
+    pub fn new(cols: usize, rows: usize, colors: Vec<Option<usize>>) -> Result<Self, String> {
+        if colors.len() != cols * rows {
+            return Err(format!(
+                "A {}x{} board needs {} colors, found {}",
+                cols,
+                rows,
+                cols * rows,
+                colors.len()
+            ));
+        }
+
+        Ok(Coloring { cols, rows, colors })
+    }
+
+    /// Build a coloring from its signature, as given by `get_signature`
+    pub fn from_signature(cols: usize, rows: usize, signature: &[u8]) -> Result<Self, String> {
+        let cells = cols * rows;
+        let plane = cells.div_ceil(8);
+        let mut colors = vec![None; cells];
+
+        for color in 0..signature.len() / plane.max(1) {
+            let members = BitBoard::from_signature(cells, &signature[color * plane..]);
+            for i in members.iter_ones() {
+                if colors[i].replace(color).is_some() {
+                    return Err(format!("Cell {} has several colors", i));
+                }
+            }
+        }
+
+        Coloring::new(cols, rows, colors)
+    }
+
+    pub fn get_cols(&self) -> &usize {
+        &self.cols
+    }
+
+    pub fn get_rows(&self) -> &usize {
+        &self.rows
+    }
+
+    pub fn get_color(&self, x: &usize, y: &usize) -> Result<&Option<usize>, String> {
+        if *x < 1 || *y < 1 || *x > self.cols || *y > self.rows {
+            return Err(format!("Coordinates ({}, {}) out of bounds", x, y));
+        }
+        Ok(&self.colors[x - 1 + self.cols * (y - 1)])
+    }
+
+    /// Number of colors used, the largest color plus one
+    pub fn get_color_count(&self) -> usize {
+        self.colors.iter().flatten().max().map_or(0, |c| c + 1)
+    }
+
+    /// Coordinates of the cells of each color, in cell order
+    pub fn get_classes(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut classes = vec![vec![]; self.get_color_count()];
+        for (i, color) in self.colors.iter().enumerate() {
+            if let Some(color) = color {
+                classes[*color].push((i % self.cols + 1, i / self.cols + 1));
+            }
+        }
+        classes
+    }
+
+    /// Signature of the board holding the pieces of each color
+    pub fn get_signatures(&self) -> Vec<Vec<u8>> {
+        (0..self.get_color_count())
+            .map(|color| {
+                let mut bits = BitBoard::new(self.cols * self.rows);
+                self.colors
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == Some(color))
+                    .for_each(|(i, _)| bits.set(i));
+                bits.to_signature()
+            })
+            .collect()
+    }
+
+    /// Signatures of all the colors, one after the other
+    pub fn get_signature(&self) -> Vec<u8> {
+        self.get_signatures().concat()
+    }
+
+    /// Whether every color holds one piece per row and column, whichever is fewer
+    pub fn splits_into_solutions(&self) -> bool {
+        let size = self.cols.min(self.rows);
+        self.get_classes().iter().all(|c| c.len() == size)
+    }
+
+    /// Colors as digits then capital letters, the top row first, and `x` for the cells without
+    /// color
+    ///
+    /// Past 36 colors every cell takes as many base 36 digits as the largest color, the cells
+    /// without color being `x` repeated as many times.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::coloring::Coloring;
+    ///
+    /// let coloring = Coloring::new(3, 1, vec![Some(0), None, Some(11)]).unwrap();
+    /// assert_eq!(coloring.to_multiline_string(), "0xB\n");
+    ///
+    /// let coloring = Coloring::new(2, 2, vec![Some(0), None, Some(36), Some(1295)]).unwrap();
+    /// assert_eq!(coloring.to_multiline_string(), "10ZZ\n00xx\n");
+    /// ```
+    pub fn to_multiline_string(&self) -> String {
+        let board = self.to_string().chars().collect::<Vec<char>>();
+        let width = self.get_symbol_width();
+
+        board
+            .chunks((self.cols * width).max(1))
+            .rev()
+            .map(|row| format!("{}\n", row.iter().collect::<String>()))
+            .collect()
+    }
+
+    /// Number of base 36 digits of the largest color
+    fn get_symbol_width(&self) -> usize {
+        let mut width = 1;
+        let mut count = self.get_color_count();
+        while count > 36 {
+            count = count.div_ceil(36);
+            width += 1;
+        }
+        width
+    }
+}
+
+impl fmt::Display for Coloring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.get_symbol_width();
+        let board = self
+            .colors
+            .iter()
+            .map(|c| match c {
+                Some(c) => (0..width)
+                    .rev()
+                    .map(|k| {
+                        let digit = (c / 36_usize.pow(k as u32)) % 36;
+                        std::char::from_digit(digit as u32, 36)
+                            .unwrap()
+                            .to_ascii_uppercase()
+                    })
+                    .collect::<String>(),
+                None => "x".repeat(width),
+            })
+            .collect::<String>();
+        write!(f, "{}", board)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::board::Board;
+use crate::piece::KNIGHT_JUMPS;
+use crate::symmetry::Symmetry;
+use std::fmt;
+
+/// Side rule on where pieces may go, on top of the attacks of the pieces
+///
+/// The `Solver` checks the constraints before every tentative placement, so they prune the
+/// moves the heuristics have to order. Equivalent boards are only skipped for the transforms
+/// that keep every constraint.
+pub trait Constraint {
+    /// Short description of the constraint
+    fn label(&self) -> String;
+
+    /// Whether a piece can go on the cell `(x, y)` with the pieces already on the board
+    fn allows(&self, board: &Board, x: &usize, y: &usize) -> bool;
+
+    /// Whether the transform of the board keeps the placements allowed by the constraint
+    fn is_invariant(&self, _board: &Board, _symmetry: &Symmetry) -> bool {
+        true
+    }
+}
+
+impl<'a> fmt::Debug for dyn Constraint + 'a {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+impl<'a> Constraint for Box<dyn Constraint + 'a> {
+    fn label(&self) -> String {
+        self.as_ref().label()
+    }
+
+    fn allows(&self, board: &Board, x: &usize, y: &usize) -> bool {
+        self.as_ref().allows(board, x, y)
+    }
+
+    fn is_invariant(&self, board: &Board, symmetry: &Symmetry) -> bool {
+        self.as_ref().is_invariant(board, symmetry)
+    }
+}
+
+/// Built-in constraint by its lowercase name
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::constraint::from_name;
+///
+/// assert_eq!(from_name("no-knight-move").unwrap().label(), "NoKnightMove");
+/// assert!(from_name("no-pawns").is_err());
+/// ```
+pub fn from_name(name: &str) -> Result<Box<dyn Constraint>, String> {
+    match name {
+        "no-three-in-line" => Ok(Box::new(NoThreeInLine)),
+        "no-knight-move" => Ok(Box::new(NoKnightMove)),
+        "no-long-diagonals" => Ok(Box::new(NoLongDiagonals)),
+        "dark-squares" => Ok(Box::new(DarkSquaresOnly)),
+        _ => Err(format!("Unknown constraint '{}'", name)),
+    }
+}
+
+/// Whether the transform maps the cells allowed by `allowed` onto themselves
+fn keeps_cells(board: &Board, symmetry: &Symmetry, allowed: impl Fn(usize, usize) -> bool) -> bool {
+    let (cols, rows) = (*board.get_cols(), *board.get_rows());
+
+    (1..=rows).all(|y| {
+        (1..=cols).all(|x| {
+            let (tx, ty) = symmetry.apply(cols, rows, &x, &y);
+            allowed(x, y) == allowed(tx, ty)
+        })
+    })
+}
+
+/// No three pieces on any straight line, whatever its slope
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::constraint::{Constraint, NoThreeInLine};
+///
+/// let mut board = Board::new(8);
+/// board.toggle_cell(&1, &1).unwrap();
+/// board.toggle_cell(&2, &3).unwrap();
+///
+/// assert!(!NoThreeInLine.allows(&board, &3, &5));
+/// assert!(NoThreeInLine.allows(&board, &3, &6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoThreeInLine;
+
+impl Constraint for NoThreeInLine {
+    fn label(&self) -> String {
+        "NoThreeInLine".to_string()
+    }
+
+    fn allows(&self, board: &Board, x: &usize, y: &usize) -> bool {
+        let (x, y) = (*x as i64, *y as i64);
+        let queens = board
+            .get_queens()
+            .iter()
+            .map(|(qx, qy)| (*qx as i64 - x, *qy as i64 - y))
+            .collect::<Vec<(i64, i64)>>();
+
+        queens
+            .iter()
+            .enumerate()
+            .all(|(k, (ax, ay))| queens[k + 1..].iter().all(|(bx, by)| ax * by != ay * bx))
+    }
+}
+
+/// No two pieces a knight's move apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoKnightMove;
+
+impl Constraint for NoKnightMove {
+    fn label(&self) -> String {
+        "NoKnightMove".to_string()
+    }
+
+    fn allows(&self, board: &Board, x: &usize, y: &usize) -> bool {
+        let (x, y) = (*x as i64, *y as i64);
+
+        !KNIGHT_JUMPS.iter().any(|(dx, dy)| {
+            let (jx, jy) = (x + dx, y + dy);
+            jx >= 1
+                && jy >= 1
+                && board
+                    .get_cell(&(jx as usize), &(jy as usize))
+                    .is_ok_and(|c| c.is_queen())
+        })
+    }
+}
+
+/// No piece on the two diagonals going through the corners, from `(1, 1)` and `(1, rows)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoLongDiagonals;
+
+impl NoLongDiagonals {
+    fn is_free(rows: usize, x: usize, y: usize) -> bool {
+        x != y && x + y != rows + 1
+    }
+}
+
+impl Constraint for NoLongDiagonals {
+    fn label(&self) -> String {
+        "NoLongDiagonals".to_string()
+    }
+
+    fn allows(&self, board: &Board, x: &usize, y: &usize) -> bool {
+        NoLongDiagonals::is_free(*board.get_rows(), *x, *y)
+    }
+
+    fn is_invariant(&self, board: &Board, symmetry: &Symmetry) -> bool {
+        let rows = *board.get_rows();
+        keeps_cells(board, symmetry, |x, y| NoLongDiagonals::is_free(rows, x, y))
+    }
+}
+
+/// Pieces only on the dark cells, the color of the corner `(1, 1)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DarkSquaresOnly;
+
+impl Constraint for DarkSquaresOnly {
+    fn label(&self) -> String {
+        "DarkSquaresOnly".to_string()
+    }
+
+    fn allows(&self, _board: &Board, x: &usize, y: &usize) -> bool {
+        (x + y).is_multiple_of(2)
+    }
+
+    fn is_invariant(&self, board: &Board, symmetry: &Symmetry) -> bool {
+        keeps_cells(board, symmetry, |x, y| (x + y).is_multiple_of(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+
+    fn solve_with(cols: usize, constraint: impl Constraint + 'static) -> Option<Board> {
+        let mut board = Board::new(cols);
+        let mut solver = Solver::new();
+        solver.push_constraint(constraint);
+
+        let result = solver.solve(&mut board).unwrap();
+        assert!(result.get_jumps() < &100000);
+
+        if result.is_solved() {
+            Some(board)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn built_in_constraints() {
+        let board = solve_with(8, NoLongDiagonals).unwrap();
+        assert!(board.get_queens().iter().all(|(x, y)| x != y && x + y != 9));
+
+        // n queens never fit on the cells of one color, at most 5 do on the chessboard
+        assert!(solve_with(8, DarkSquaresOnly).is_none());
+        let mut board = Board::new(8);
+        let mut solver = Solver::new();
+        solver.push_constraint(DarkSquaresOnly);
+        solver.solve_maximum(&mut board).unwrap();
+        assert_eq!(board.get_queens().len(), 5);
+        assert!(board.get_queens().iter().all(|(x, y)| (x + y) % 2 == 0));
+
+        let board = solve_with(8, NoThreeInLine).unwrap();
+        let queens = board.get_queens();
+        for (k, a) in queens.iter().enumerate() {
+            let mut others = queens.clone();
+            others.remove(k);
+            let mut probe = Board::new(8);
+            for (x, y) in &others {
+                probe.toggle_cell(x, y).unwrap();
+            }
+            assert!(NoThreeInLine.allows(&probe, &a.0, &a.1));
+        }
+
+        // Queens without knight moves between them are the superqueens, none fit below 10
+        assert!(solve_with(8, NoKnightMove).is_none());
+    }
+
+    #[test]
+    fn invariance() {
+        let board = Board::new(8);
+        assert!(NoLongDiagonals.is_invariant(&board, &Symmetry::Rotate90));
+        assert!(!DarkSquaresOnly.is_invariant(&board, &Symmetry::Rotate90));
+        assert!(DarkSquaresOnly.is_invariant(&board, &Symmetry::ReflectDiagonal));
+
+        let board = Board::new(7);
+        assert!(DarkSquaresOnly.is_invariant(&board, &Symmetry::Rotate90));
+    }
+}
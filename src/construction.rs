@@ -0,0 +1,81 @@
+/// Build a solution without searching, in linear time
+///
+/// Uses the classic explicit construction that splits the even and odd columns, with a few
+/// swaps when the number of columns leaves a remainder of 2 or 3 when divided by 6. The result
+/// is a permutation: the `k`-th element is the column of the queen in row `k + 1`.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::construction::construct_solution;
+///
+/// let permutation = construct_solution(8).unwrap();
+/// let board = Board::from_permutation(&permutation).unwrap();
+///
+/// assert!(board.is_solved());
+/// assert!(construct_solution(3).is_err());
+/// ```
+pub fn construct_solution(cols: usize) -> Result<Vec<usize>, String> {
+    if cols == 2 || cols == 3 {
+        return Err(format!("There is no solution for {} columns", cols));
+    }
+
+    let mut evens = (2..=cols).step_by(2).collect::<Vec<usize>>();
+    let mut odds = (1..=cols).step_by(2).collect::<Vec<usize>>();
+
+    match cols % 6 {
+        2 => {
+            // 3, 1, 7, 9, ..., 5
+            odds.swap(0, 1);
+            let five = odds.remove(2);
+            odds.push(five);
+        }
+        3 => {
+            // 4, 6, ..., 2 and 5, 7, ..., 1, 3
+            evens.rotate_left(1);
+            odds.rotate_left(2);
+        }
+        _ => {}
+    }
+
+    evens.append(&mut odds);
+
+    Ok(evens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn valid_solutions() {
+        for cols in (1..=60).filter(|c| *c != 2 && *c != 3) {
+            let permutation = construct_solution(cols).unwrap();
+            let board = Board::from_permutation(&permutation).unwrap();
+
+            assert!(board.is_solved(), "{} columns", cols);
+        }
+    }
+
+    #[test]
+    fn large_board() {
+        let cols = 1_000_003;
+        let permutation = construct_solution(cols).unwrap();
+
+        let mut diagonals = vec![false; 2 * cols];
+        let mut anti_diagonals = vec![false; 2 * cols];
+        let mut columns = vec![false; cols + 1];
+
+        for (row, col) in permutation.iter().enumerate() {
+            assert!(!columns[*col]);
+            assert!(!diagonals[row + col]);
+            assert!(!anti_diagonals[row + cols - col]);
+
+            columns[*col] = true;
+            diagonals[row + col] = true;
+            anti_diagonals[row + cols - col] = true;
+        }
+    }
+}
@@ -0,0 +1,629 @@
+use crate::collinear::CollinearCells;
+use crate::symmetry::Symmetry;
+use std::thread;
+
+/// Largest number of columns supported by the counting engine
+///
+/// Diagonals are tracked in a single `u64`, so they must fit in `2 * cols - 1` bits.
+pub const MAX_COUNT_COLS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolutionCount {
+    total: u64,
+    fundamental: u64,
+}
+
+impl SolutionCount {
+    pub fn new(total: u64, fundamental: u64) -> Self {
+        SolutionCount { total, fundamental }
+    }
+
+    /// Number of distinct solutions
+    pub fn get_total(&self) -> &u64 {
+        &self.total
+    }
+
+    /// Number of solutions unique up to rotations and reflections
+    pub fn get_fundamental(&self) -> &u64 {
+        &self.fundamental
+    }
+}
+
+/// Count every solution of a board with `cols` columns
+///
+/// The total is found with a bit-parallel row by row backtracking over the column and diagonal
+/// masks, only exploring the left half of the first row and doubling the result. The fundamental
+/// count follows from Burnside's lemma: the only symmetries a solution may have are the 90 and
+/// 180 degrees rotations, so the orbits are `(total + 2 * fixed_90 + fixed_180) / 8`.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::counter::count_solutions;
+///
+/// let count = count_solutions(8).unwrap();
+///
+/// assert_eq!(count.get_total(), &92);
+/// assert_eq!(count.get_fundamental(), &12);
+/// ```
+pub fn count_solutions(cols: usize) -> Result<SolutionCount, String> {
+    if cols > MAX_COUNT_COLS {
+        return Err(format!(
+            "Unable to count solutions for more than {} columns",
+            MAX_COUNT_COLS
+        ));
+    }
+
+    let total = count_total(cols);
+    let fixed_90 = count_symmetric(cols, |n, r, c| (c, n - 1 - r));
+    let fixed_180 = count_symmetric(cols, |n, r, c| (n - 1 - r, n - 1 - c));
+
+    // Reflections only fix the trivial boards
+    let fixed_reflections = if cols <= 1 { total } else { 0 };
+
+    let fundamental = (total + 2 * fixed_90 + fixed_180 + 4 * fixed_reflections) / 8;
+
+    Ok(SolutionCount::new(total, fundamental))
+}
+
+fn count_total(cols: usize) -> u64 {
+    if cols == 0 {
+        return 1;
+    }
+
+    let full = (1_u64 << cols) - 1;
+    let half = cols / 2;
+
+    // Every column of the first row is an independent subtree
+    let counts = thread::scope(|s| {
+        let handles = (0..half + cols % 2)
+            .map(|c| {
+                s.spawn(move || {
+                    let bit = 1 << c;
+                    count_from(full, bit, (bit << 1) & full, bit >> 1)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("Counting thread panicked"))
+            .collect::<Vec<u64>>()
+    });
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(c, count)| if c < half { 2 * count } else { *count })
+        .sum()
+}
+
+fn count_from(full: u64, columns: u64, left: u64, right: u64) -> u64 {
+    if columns == full {
+        return 1;
+    }
+
+    let mut count = 0;
+    let mut free = full & !(columns | left | right);
+
+    while free != 0 {
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+        count += count_from(
+            full,
+            columns | bit,
+            ((left | bit) << 1) & full,
+            (right | bit) >> 1,
+        );
+    }
+
+    count
+}
+
+/// Count every solution of a board with `cols` columns glued as a torus
+///
+/// Diagonals wrap around the edges, as in modular n-queens, so there are solutions only when
+/// `cols` is coprime with 6. Translations map solutions onto solutions, so only the solutions
+/// with a queen in the first cell are searched, and the total is `cols` times their number.
+/// The fundamental count is up to translations, rotations and reflections, again by Burnside's
+/// lemma over the stabilizers of the searched solutions.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::counter::count_toroidal_solutions;
+///
+/// let count = count_toroidal_solutions(7).unwrap();
+///
+/// assert_eq!(count.get_total(), &28);
+/// assert_eq!(count.get_fundamental(), &1);
+/// assert_eq!(count_toroidal_solutions(8).unwrap().get_total(), &0);
+/// ```
+pub fn count_toroidal_solutions(cols: usize) -> Result<SolutionCount, String> {
+    if cols > MAX_COUNT_COLS {
+        return Err(format!(
+            "Unable to count solutions for more than {} columns",
+            MAX_COUNT_COLS
+        ));
+    }
+
+    if cols == 0 {
+        return Ok(SolutionCount::new(1, 1));
+    }
+
+    if cols > 1 && (cols.is_multiple_of(2) || cols.is_multiple_of(3)) {
+        return Ok(SolutionCount::new(0, 0));
+    }
+
+    let full = (1_u64 << cols) - 1;
+    let mut permutation = vec![0; cols];
+    let mut anchored = 0;
+    let mut fixed = 0;
+
+    count_toroidal_from(
+        cols,
+        full,
+        1,
+        rotate(cols, 1, 1),
+        rotate(cols, 1, cols - 1),
+        &mut permutation,
+        &mut |p| {
+            anchored += 1;
+            fixed += toroidal_stabilizer(p);
+        },
+    );
+
+    let total = cols as u64 * anchored;
+    let fundamental = fixed / (8 * cols as u64);
+
+    Ok(SolutionCount::new(total, fundamental))
+}
+
+/// Rotate the `cols` low bits of a mask `by` positions to the left
+fn rotate(cols: usize, mask: u64, by: usize) -> u64 {
+    let full = (1_u64 << cols) - 1;
+    ((mask << by) | (mask >> (cols - by))) & full
+}
+
+fn count_toroidal_from<F>(
+    cols: usize,
+    full: u64,
+    columns: u64,
+    left: u64,
+    right: u64,
+    permutation: &mut Vec<usize>,
+    found: &mut F,
+) where
+    F: FnMut(&[usize]),
+{
+    let row = columns.count_ones() as usize;
+    if row == cols {
+        found(permutation);
+        return;
+    }
+
+    let mut free = full & !(columns | left | right);
+
+    while free != 0 {
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+
+        permutation[row] = bit.trailing_zeros() as usize;
+        count_toroidal_from(
+            cols,
+            full,
+            columns | bit,
+            rotate(cols, left | bit, 1),
+            rotate(cols, right | bit, cols - 1),
+            permutation,
+            found,
+        );
+    }
+}
+
+/// Number of translations, rotations and reflections of the torus fixing a solution
+fn toroidal_stabilizer(permutation: &[usize]) -> u64 {
+    let cols = permutation.len();
+    let mut fixed = 0;
+
+    for s in Symmetry::ALL.iter() {
+        let mut image = vec![0; cols];
+        for (y, x) in permutation.iter().enumerate() {
+            let (tx, ty) = s.apply(cols, cols, &(x + 1), &(y + 1));
+            image[ty - 1] = tx - 1;
+        }
+
+        // The vertical shift decides the horizontal one, from the first row
+        for shift in 0..cols {
+            let row = |y: usize| image[(y + cols - shift) % cols];
+            let offset = (permutation[0] + cols - row(0)) % cols;
+
+            if (0..cols).all(|y| (row(y) + offset) % cols == permutation[y]) {
+                fixed += 1;
+            }
+        }
+    }
+
+    fixed
+}
+
+/// Count the solutions of a board with `cols` columns where no three queens are in line
+///
+/// Lines of every slope count, not only the rows, columns and diagonals the queens attack. The
+/// row by row backtracking keeps the column and diagonal masks, and blocks the cells in line
+/// with two placed queens through `CollinearCells`. The fundamental count is by Burnside's
+/// lemma over the stabilizers of the solutions found.
+///
+/// Verified counts, total and fundamental: 2 and 1 for 4 columns, 8 and 1 for 8, 32 and 4 for
+/// 9, 40 and 5 for 10, 96 and 12 for 11, 410 and 53 for 12, none for 2, 3, 5, 6 and 7.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::counter::count_no_three_in_line;
+///
+/// let count = count_no_three_in_line(8).unwrap();
+///
+/// assert_eq!(count.get_total(), &8);
+/// assert_eq!(count.get_fundamental(), &1);
+/// ```
+pub fn count_no_three_in_line(cols: usize) -> Result<SolutionCount, String> {
+    if cols > MAX_COUNT_COLS {
+        return Err(format!(
+            "Unable to count solutions for more than {} columns",
+            MAX_COUNT_COLS
+        ));
+    }
+
+    if cols == 0 {
+        return Ok(SolutionCount::new(1, 1));
+    }
+
+    let full = (1_u64 << cols) - 1;
+    let mut lines = CollinearCells::new(cols, cols);
+    let mut permutation = vec![0; cols];
+    let mut total = 0;
+    let mut fixed = 0;
+
+    count_no_three_in_line_from(
+        cols,
+        full,
+        (0, 0, 0),
+        &mut lines,
+        &mut permutation,
+        &mut |p| {
+            total += 1;
+            fixed += stabilizer(p);
+        },
+    );
+
+    Ok(SolutionCount::new(total, fixed / 8))
+}
+
+fn count_no_three_in_line_from<F>(
+    cols: usize,
+    full: u64,
+    (columns, left, right): (u64, u64, u64),
+    lines: &mut CollinearCells,
+    permutation: &mut Vec<usize>,
+    found: &mut F,
+) where
+    F: FnMut(&[usize]),
+{
+    let row = columns.count_ones() as usize;
+    if row == cols {
+        found(permutation);
+        return;
+    }
+
+    let mut free = full & !(columns | left | right);
+
+    while free != 0 {
+        let bit = free & free.wrapping_neg();
+        free ^= bit;
+
+        let (x, y) = (bit.trailing_zeros() as usize + 1, row + 1);
+        if lines.is_blocked(&x, &y) {
+            continue;
+        }
+
+        permutation[row] = x - 1;
+        lines.toggle(&x, &y).unwrap();
+        count_no_three_in_line_from(
+            cols,
+            full,
+            (columns | bit, (left | bit) << 1, (right | bit) >> 1),
+            lines,
+            permutation,
+            found,
+        );
+        lines.toggle(&x, &y).unwrap();
+    }
+}
+
+/// Number of rotations and reflections fixing a solution
+fn stabilizer(permutation: &[usize]) -> u64 {
+    let cols = permutation.len();
+
+    Symmetry::ALL
+        .iter()
+        .filter(|s| {
+            permutation.iter().enumerate().all(|(y, x)| {
+                let (tx, ty) = s.apply(cols, cols, &(x + 1), &(y + 1));
+                permutation[ty - 1] == tx - 1
+            })
+        })
+        .count() as u64
+}
+
+/// Iterator over every solution of a board, as permutations
+///
+/// The `k`-th element of a permutation is the column of the queen in row `k + 1`, both
+/// starting at 1 like the board coordinates.
+pub struct Solutions {
+    cols: usize,
+    full: u64,
+    stack: Vec<(u64, u64, u64, u64)>,
+    permutation: Vec<usize>,
+    /// Whether the empty solution of a board without columns is still to be given
+    empty: bool,
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.empty {
+            self.empty = false;
+            return Some(vec![]);
+        }
+
+        while let Some((free, columns, left, right)) = self.stack.last_mut() {
+            if *free == 0 {
+                self.stack.pop();
+                continue;
+            }
+
+            let bit = *free & free.wrapping_neg();
+            *free ^= bit;
+
+            let (columns, left, right) = (*columns | bit, *left | bit, *right | bit);
+            let depth = self.stack.len();
+            self.permutation[depth - 1] = bit.trailing_zeros() as usize + 1;
+
+            if depth == self.cols {
+                return Some(self.permutation.clone());
+            }
+
+            let (left, right) = ((left << 1) & self.full, right >> 1);
+            self.stack
+                .push((self.full & !(columns | left | right), columns, left, right));
+        }
+
+        None
+    }
+}
+
+/// Enumerate the solutions of a board in lexicographic order
+///
+/// A board without columns has a single solution, the empty one, as counted by
+/// `count_solutions`.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::counter::solutions;
+///
+/// let all = solutions(4).unwrap().collect::<Vec<Vec<usize>>>();
+///
+/// assert_eq!(all, vec![vec![2, 4, 1, 3], vec![3, 1, 4, 2]]);
+/// ```
+pub fn solutions(cols: usize) -> Result<Solutions, String> {
+    if cols > MAX_COUNT_COLS {
+        return Err(format!(
+            "Unable to enumerate solutions for more than {} columns",
+            MAX_COUNT_COLS
+        ));
+    }
+
+    let full = if cols == 0 { 0 } else { (1_u64 << cols) - 1 };
+    let stack = if cols == 0 {
+        vec![]
+    } else {
+        vec![(full, 0, 0, 0)]
+    };
+
+    Ok(Solutions {
+        cols,
+        full,
+        stack,
+        permutation: vec![0; cols],
+        empty: cols == 0,
+    })
+}
+
+/// Occupied lines of a partially filled board, used by the symmetric search
+#[derive(Clone, Copy)]
+struct Lines {
+    rows: u64,
+    columns: u64,
+    diagonals: u64,
+    anti_diagonals: u64,
+}
+
+impl Lines {
+    fn place(&self, cols: usize, r: usize, c: usize) -> Option<Lines> {
+        let (row, column) = (1 << r, 1 << c);
+        let diagonal = 1 << (r + c);
+        let anti_diagonal = 1 << (r + cols - 1 - c);
+
+        if self.rows & row != 0
+            || self.columns & column != 0
+            || self.diagonals & diagonal != 0
+            || self.anti_diagonals & anti_diagonal != 0
+        {
+            return None;
+        }
+
+        Some(Lines {
+            rows: self.rows | row,
+            columns: self.columns | column,
+            diagonals: self.diagonals | diagonal,
+            anti_diagonals: self.anti_diagonals | anti_diagonal,
+        })
+    }
+}
+
+/// Count the solutions invariant under the rotation `transform`
+///
+/// Queens are placed a whole orbit at a time, so the search only visits symmetric boards.
+fn count_symmetric<F>(cols: usize, transform: F) -> u64
+where
+    F: Fn(usize, usize, usize) -> (usize, usize) + Copy,
+{
+    let lines = Lines {
+        rows: 0,
+        columns: 0,
+        diagonals: 0,
+        anti_diagonals: 0,
+    };
+
+    count_symmetric_from(cols, transform, lines)
+}
+
+fn count_symmetric_from<F>(cols: usize, transform: F, lines: Lines) -> u64
+where
+    F: Fn(usize, usize, usize) -> (usize, usize) + Copy,
+{
+    let r = (!lines.rows).trailing_zeros() as usize;
+    if r >= cols {
+        return 1;
+    }
+
+    let mut count = 0;
+
+    for c in (0..cols).filter(|c| lines.columns & (1 << c) == 0) {
+        let mut orbit = vec![(r, c)];
+        let mut next = transform(cols, r, c);
+        while next != (r, c) {
+            orbit.push(next);
+            next = transform(cols, next.0, next.1);
+        }
+
+        let placed = orbit
+            .iter()
+            .try_fold(lines, |l, (x, y)| l.place(cols, *x, *y));
+
+        if let Some(l) = placed {
+            count += count_symmetric_from(cols, transform, l);
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Solutions of the boards from 0 to 17 columns
+    const TOTAL: [u64; 18] = [
+        1, 1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200, 73712, 365596, 2279184, 14772512,
+        95815104,
+    ];
+    const FUNDAMENTAL: [u64; 18] = [
+        1, 1, 0, 0, 1, 2, 1, 6, 12, 46, 92, 341, 1787, 9233, 45752, 285053, 1846955, 11977939,
+    ];
+
+    fn check_sequence(columns: std::ops::RangeInclusive<usize>) {
+        for cols in columns {
+            let count = count_solutions(cols).unwrap();
+            assert_eq!(count.get_total(), &TOTAL[cols], "total for {}", cols);
+            assert_eq!(
+                count.get_fundamental(),
+                &FUNDAMENTAL[cols],
+                "fundamental for {}",
+                cols
+            );
+        }
+    }
+
+    #[test]
+    fn known_sequence() {
+        check_sequence(0..=16);
+    }
+
+    // Run by the CI with optimizations, see .travis.yml
+    #[test]
+    #[ignore = "takes minutes without optimizations"]
+    fn known_sequence_slow() {
+        check_sequence(17..=17);
+    }
+
+    #[test]
+    fn enumerated_solutions() {
+        assert_eq!(solutions(0).unwrap().collect::<Vec<_>>(), vec![vec![]]);
+
+        for cols in 0..=9 {
+            let count = solutions(cols).unwrap().count() as u64;
+            assert_eq!(&count, count_solutions(cols).unwrap().get_total());
+        }
+    }
+
+    #[test]
+    fn toroidal_sequence() {
+        let expected = [
+            (1, 1, 1),
+            (4, 0, 0),
+            (5, 10, 1),
+            (7, 28, 1),
+            (11, 88, 2),
+            (13, 4524, 11),
+        ];
+
+        for (cols, total, fundamental) in expected.iter() {
+            let count = count_toroidal_solutions(*cols).unwrap();
+            assert_eq!(count.get_total(), total, "total for {}", cols);
+            assert_eq!(
+                count.get_fundamental(),
+                fundamental,
+                "fundamental for {}",
+                cols
+            );
+        }
+    }
+
+    #[test]
+    fn no_three_in_line_sequence() {
+        let expected = [
+            (1, 1, 1),
+            (2, 0, 0),
+            (3, 0, 0),
+            (4, 2, 1),
+            (5, 0, 0),
+            (6, 0, 0),
+            (7, 0, 0),
+            (8, 8, 1),
+            (9, 32, 4),
+            (10, 40, 5),
+            (11, 96, 12),
+            (12, 410, 53),
+        ];
+
+        for (cols, total, fundamental) in expected.iter() {
+            let count = count_no_three_in_line(*cols).unwrap();
+            assert_eq!(count.get_total(), total, "total for {}", cols);
+            assert_eq!(
+                count.get_fundamental(),
+                fundamental,
+                "fundamental for {}",
+                cols
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_columns() {
+        assert!(count_solutions(MAX_COUNT_COLS + 1).is_err());
+    }
+}
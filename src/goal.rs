@@ -0,0 +1,269 @@
+use crate::board::Board;
+use crate::cell::CellContent;
+use crate::symmetry::Symmetry;
+use std::fmt;
+
+/// What the `Solver` is looking for
+///
+/// The search stops as soon as a board reaches the goal, and otherwise keeps the board with
+/// the most progress. The solver skips the boards equivalent to the ones already explored,
+/// so a goal must tell the transforms that don't change whether it is reached.
+pub trait Goal {
+    /// Short description of the goal
+    fn label(&self) -> String;
+
+    fn is_reached(&self, board: &Board) -> bool;
+
+    /// How far the board is from the goal, higher is better
+    ///
+    /// Defaults to the number of pieces on the board.
+    fn progress(&self, board: &Board) -> usize {
+        board.get_occupancy().count_ones()
+    }
+
+    /// Whether the transform keeps the boards reaching the goal, and their progress
+    fn is_invariant(&self, _symmetry: &Symmetry) -> bool {
+        true
+    }
+}
+
+impl<'a> fmt::Debug for dyn Goal + 'a {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Place exactly `k` pieces
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::goal::ExactCount;
+/// use andaluz_core::solver::Solver;
+///
+/// let mut board = Board::new(8);
+///
+/// let mut solver = Solver::new();
+/// solver.set_goal(ExactCount::new(5));
+///
+/// assert!(solver.solve(&mut board).unwrap().is_solved());
+/// assert_eq!(board.get_queens().len(), 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExactCount {
+    count: usize,
+}
+
+impl ExactCount {
+    pub fn new(count: usize) -> Self {
+        ExactCount { count }
+    }
+}
+
+impl Goal for ExactCount {
+    fn label(&self) -> String {
+        format!("ExactCount({})", self.count)
+    }
+
+    fn is_reached(&self, board: &Board) -> bool {
+        board.get_occupancy().count_ones() == self.count
+    }
+}
+
+/// Place as many pieces as possible
+///
+/// Only reached once the pieces fill the bound of `Board::get_piece_bound`, otherwise the
+/// search goes on until it runs out of boards or jumps, and the solver reports the most
+/// pieces placed.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::goal::MaximizeCount;
+/// use andaluz_core::solver::Solver;
+///
+/// let mut board = Board::new(4);
+/// board.toggle_hole(&2, &1).unwrap();
+/// board.toggle_hole(&3, &1).unwrap();
+///
+/// let mut solver = Solver::new();
+/// solver.set_goal(MaximizeCount::new());
+///
+/// let result = solver.solve(&mut board).unwrap();
+///
+/// assert!(!result.is_solved());
+/// assert_eq!(result.get_best_progress(), &3);
+/// assert_eq!(board.get_queens().len(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MaximizeCount;
+
+impl MaximizeCount {
+    pub fn new() -> Self {
+        MaximizeCount
+    }
+}
+
+impl Goal for MaximizeCount {
+    fn label(&self) -> String {
+        "MaximizeCount".to_string()
+    }
+
+    fn is_reached(&self, board: &Board) -> bool {
+        board.get_occupancy().count_ones() >= board.get_piece_bound()
+    }
+}
+
+/// Put a piece on every row that has a cell able to hold one
+///
+/// Progress is the number of rows holding a piece. Only the transforms that keep the rows
+/// are symmetries of this goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllRowsFilled;
+
+impl AllRowsFilled {
+    pub fn new() -> Self {
+        AllRowsFilled
+    }
+}
+
+impl Goal for AllRowsFilled {
+    fn label(&self) -> String {
+        "AllRowsFilled".to_string()
+    }
+
+    fn is_reached(&self, board: &Board) -> bool {
+        (1..=*board.get_rows()).all(|y| {
+            let contents = (1..=*board.get_cols())
+                .filter_map(|x| board.get_cell_content(&x, &y).ok())
+                .collect::<Vec<&CellContent>>();
+
+            contents.contains(&&CellContent::Queen)
+                || contents
+                    .iter()
+                    .all(|c| c == &&CellContent::Blocked || c == &&CellContent::Obstacle)
+        })
+    }
+
+    fn progress(&self, board: &Board) -> usize {
+        let mut rows = board
+            .get_queens()
+            .iter()
+            .map(|(_, y)| *y)
+            .collect::<Vec<usize>>();
+        rows.dedup();
+        rows.len()
+    }
+
+    fn is_invariant(&self, symmetry: &Symmetry) -> bool {
+        Symmetry::RECTANGLE.contains(symmetry)
+    }
+}
+
+/// Goal reached when a user-defined predicate holds
+///
+/// Nothing is known about the predicate, so the solver doesn't skip equivalent boards.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::goal::Predicate;
+/// use andaluz_core::solver::Solver;
+///
+/// let mut board = Board::new(6);
+///
+/// let mut solver = Solver::new();
+/// solver.set_goal(Predicate::new("CornerAndFour", |b: &Board| {
+///     b.get_queens().len() == 4 && b.get_queens().contains(&(1, 1))
+/// }));
+///
+/// assert!(solver.solve(&mut board).unwrap().is_solved());
+/// assert!(board.get_queens().contains(&(1, 1)));
+/// ```
+pub struct Predicate<'a> {
+    label: &'a str,
+    predicate: Box<dyn Fn(&Board) -> bool + 'a>,
+}
+
+impl<'a> Predicate<'a> {
+    pub fn new(label: &'a str, predicate: impl Fn(&Board) -> bool + 'a) -> Self {
+        Predicate {
+            label,
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+impl<'a> Goal for Predicate<'a> {
+    fn label(&self) -> String {
+        self.label.to_string()
+    }
+
+    fn is_reached(&self, board: &Board) -> bool {
+        (self.predicate)(board)
+    }
+
+    fn is_invariant(&self, symmetry: &Symmetry) -> bool {
+        symmetry == &Symmetry::Identity
+    }
+}
+
+impl<'a> fmt::Debug for Predicate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+    use crate::solver::Solver;
+
+    #[test]
+    fn rows_of_knights() {
+        // Knights fill the rows of a 4x4 board, but not with one per column
+        let mut board = Board::new(4);
+        board.set_piece(Piece::knight()).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_cell(&1, &2).unwrap();
+
+        assert!(!AllRowsFilled.is_reached(&board));
+        assert_eq!(AllRowsFilled.progress(&board), 2);
+
+        let mut solver = Solver::new();
+        solver.set_goal(AllRowsFilled::new());
+        assert!(solver.solve(&mut board).unwrap().is_solved());
+        assert!(AllRowsFilled.is_reached(&board));
+
+        // Rows made only of holes don't need a piece
+        let mut board = Board::new(3);
+        for x in 1..=3 {
+            board.toggle_hole(&x, &2).unwrap();
+        }
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_cell(&2, &3).unwrap();
+        assert!(AllRowsFilled.is_reached(&board));
+    }
+
+    #[test]
+    fn maximize_is_exhaustive() {
+        // Without reaching the bound, the best count comes from the whole search
+        let mut board = Board::new(5);
+        board.toggle_obstacle(&3, &3).unwrap();
+
+        let mut solver = Solver::new();
+        solver.set_goal(MaximizeCount::new());
+        let result = solver.solve(&mut board).unwrap();
+
+        let mut maximum = Board::new(5);
+        maximum.toggle_obstacle(&3, &3).unwrap();
+        Solver::new().solve_maximum(&mut maximum).unwrap();
+
+        assert_eq!(result.get_best_progress(), &maximum.get_queens().len());
+        assert_eq!(board.get_queens().len(), maximum.get_queens().len());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+
+/// Graph of the cells of a board, two cells joined when a piece on one attacks the other
+///
+/// Vertices are cell indices, `x - 1 + cols * (y - 1)`, and edges are undirected, joining two
+/// cells when either one attacks the other. The graph can be written for the usual graph
+/// tools as DOT, GraphML or a plain edge list.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::board::Board;
+/// use andaluz_core::graph::AttackGraph;
+///
+/// let graph = AttackGraph::from_board(&Board::new(3)).unwrap();
+///
+/// assert_eq!(graph.get_vertices().len(), 9);
+/// assert_eq!(graph.get_edges().len(), 28);
+/// assert_eq!(graph.get_degree(4), 8);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttackGraph {
+    cols: usize,
+    rows: usize,
+    vertices: Vec<usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl AttackGraph {
+    /// Graph of every cell that can hold a piece, ignoring the pieces placed
+    ///
+    /// Holes and obstacles are left out, obstacles still stopping the attacks.
+    pub fn from_board(board: &Board) -> Result<Self, String> {
+        let mut empty = board.clone();
+        empty.reset();
+        empty.set_mixture(&[])?;
+
+        let vertices = empty
+            .get_cells()
+            .iter()
+            .filter(|c| !c.is_blocked() && !c.is_obstacle())
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+
+        Ok(AttackGraph::induced(&empty, vertices))
+    }
+
+    /// Graph induced on the cells still available for a piece on a partial board
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::graph::AttackGraph;
+    ///
+    /// let mut board = Board::new(4);
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// let graph = AttackGraph::from_available(&board);
+    ///
+    /// assert_eq!(graph.get_vertices(), &vec![6, 7, 9, 11, 13, 14]);
+    /// assert_eq!(graph.get_edges().len(), 11);
+    /// ```
+    pub fn from_available(board: &Board) -> Self {
+        let mut vertices = board
+            .get_available_cells()
+            .iter()
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+        vertices.sort_unstable();
+
+        AttackGraph::induced(board, vertices)
+    }
+
+    fn induced(board: &Board, vertices: Vec<usize>) -> Self {
+        let (cols, rows) = (*board.get_cols(), *board.get_rows());
+
+        let mut chosen = BitBoard::new(cols * rows);
+        vertices.iter().for_each(|v| chosen.set(*v));
+
+        let mut edges = vec![];
+        for v in &vertices {
+            for u in board.get_attack_mask(*v).iter_ones() {
+                if u != *v && chosen.get(u) {
+                    edges.push((u.min(*v), u.max(*v)));
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        AttackGraph {
+            cols,
+            rows,
+            vertices,
+            edges,
+        }
+    }
+
+    pub fn get_cols(&self) -> &usize {
+        &self.cols
+    }
+
+    pub fn get_rows(&self) -> &usize {
+        &self.rows
+    }
+
+    /// Cell indices of the vertices, in cell order
+    pub fn get_vertices(&self) -> &Vec<usize> {
+        &self.vertices
+    }
+
+    /// Pairs of cell indices, the smaller first, in order
+    pub fn get_edges(&self) -> &Vec<(usize, usize)> {
+        &self.edges
+    }
+
+    /// Number of edges at the cell `i`
+    pub fn get_degree(&self, i: usize) -> usize {
+        self.edges
+            .iter()
+            .filter(|(a, b)| *a == i || *b == i)
+            .count()
+    }
+
+    fn get_xy(&self, i: usize) -> (usize, usize) {
+        (i % self.cols + 1, i / self.cols + 1)
+    }
+
+    /// Undirected DOT graph, each vertex labelled with its coordinates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::graph::AttackGraph;
+    ///
+    /// let board = Board::new_rectangle(2, 1);
+    /// let graph = AttackGraph::from_board(&board).unwrap();
+    ///
+    /// assert_eq!(
+    ///     graph.to_dot(),
+    ///     "graph attacks {\n  0 [label=\"1,1\"];\n  1 [label=\"2,1\"];\n  0 -- 1;\n}\n"
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = "graph attacks {\n".to_string();
+        for v in &self.vertices {
+            let (x, y) = self.get_xy(*v);
+            dot.push_str(&format!("  {} [label=\"{},{}\"];\n", v, x, y));
+        }
+        for (a, b) in &self.edges {
+            dot.push_str(&format!("  {} -- {};\n", a, b));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// GraphML document, the coordinates of each vertex as the `x` and `y` attributes
+    pub fn to_graphml(&self) -> String {
+        let mut xml = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"x\" for=\"node\" attr.name=\"x\" attr.type=\"int\"/>\n",
+            "  <key id=\"y\" for=\"node\" attr.name=\"y\" attr.type=\"int\"/>\n",
+            "  <graph id=\"attacks\" edgedefault=\"undirected\">\n",
+        )
+        .to_string();
+        for v in &self.vertices {
+            let (x, y) = self.get_xy(*v);
+            xml.push_str(&format!(
+                "    <node id=\"n{}\"><data key=\"x\">{}</data><data key=\"y\">{}</data></node>\n",
+                v, x, y
+            ));
+        }
+        for (a, b) in &self.edges {
+            xml.push_str(&format!(
+                "    <edge source=\"n{}\" target=\"n{}\"/>\n",
+                a, b
+            ));
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// One edge per line, as the two cell indices separated by a space
+    ///
+    /// Vertices without edges don't show up in the list.
+    pub fn to_edge_list(&self) -> String {
+        self.edges
+            .iter()
+            .map(|(a, b)| format!("{} {}\n", a, b))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piece::Piece;
+
+    #[test]
+    fn queens_graph_sizes() {
+        // The queens graph of side n has n (n - 1) (5n - 1) / 3 edges
+        for n in 1..=9 {
+            let graph = AttackGraph::from_board(&Board::new(n)).unwrap();
+            assert_eq!(graph.get_edges().len(), n * (n - 1) * (5 * n - 1) / 3);
+        }
+
+        let mut board = Board::new(8);
+        board.set_piece(Piece::knight()).unwrap();
+        let graph = AttackGraph::from_board(&board).unwrap();
+        assert_eq!(graph.get_edges().len(), 168);
+
+        board.toggle_hole(&1, &1).unwrap();
+        let graph = AttackGraph::from_board(&board).unwrap();
+        assert_eq!(graph.get_vertices().len(), 63);
+        assert_eq!(graph.get_edges().len(), 166);
+        assert_eq!(graph.to_edge_list().lines().count(), 166);
+        assert_eq!(graph.to_graphml().matches("<node ").count(), 63);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::board::Board;
 use std::fmt;
 
+pub type HeuristicFn = Box<dyn Fn(&Board, &usize, &usize) -> f64>;
+
 pub struct Heuristic<'a> {
     label: &'a str,
     weigth: f64,
-    implementation: Box<Fn(&Board, &usize, &usize) -> f64>,
+    implementation: HeuristicFn,
 }
 
 impl<'a> fmt::Debug for Heuristic<'a> {
@@ -14,11 +16,7 @@ impl<'a> fmt::Debug for Heuristic<'a> {
 }
 
 impl<'a> Heuristic<'a> {
-    pub fn new(
-        label: &'a str,
-        weigth: f64,
-        implementation: Box<Fn(&Board, &usize, &usize) -> f64>,
-    ) -> Self {
+    pub fn new(label: &'a str, weigth: f64, implementation: HeuristicFn) -> Self {
         Heuristic {
             label,
             weigth,
@@ -20,11 +20,12 @@ impl HeuristicImplementation for AttackSumInverse {
                     .get_cells()
                     .iter()
                     .map(|c| match c.get_content() {
-                        &CellContent::Attack(a) => a as f64,
+                        CellContent::Attack(a) => *a as f64,
+                        CellContent::Armies(w, b) => (w + b) as f64,
                         _ => 0.0,
                     })
                     .sum();
-                let maximum = 4.0 * (board.get_cols() * board.get_cols()) as f64;
+                let maximum = 4.0 * (board.get_cols() * board.get_rows()) as f64;
                 (maximum - sum_attack) / maximum
             }),
         )
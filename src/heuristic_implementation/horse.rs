@@ -2,6 +2,7 @@ use crate::board::Board;
 use crate::cell::CellContent;
 use crate::heuristic::Heuristic;
 use crate::heuristic_implementation::HeuristicImplementation;
+use crate::piece::KNIGHT_JUMPS;
 
 pub struct Horse {
     pub weigth: f64,
@@ -9,9 +10,9 @@ pub struct Horse {
 
 impl Horse {
     fn check_jump(board: &Board, x: i32, y: i32) -> bool {
-        let cols = *board.get_cols() as i32;
+        let (cols, rows) = (*board.get_cols() as i32, *board.get_rows() as i32);
 
-        if x < 1 || x > cols || y < 1 || y > cols {
+        if x < 1 || x > cols || y < 1 || y > rows {
             return false;
         }
 
@@ -33,17 +34,11 @@ impl HeuristicImplementation for Horse {
             "Horse",
             self.weigth,
             Box::new(|board, x, y| {
-                let mut horse = false;
                 let (sx, sy) = (*x as i32, *y as i32);
 
-                horse = horse || Horse::check_jump(board, sx - 1, sy - 2);
-                horse = horse || Horse::check_jump(board, sx - 2, sy - 1);
-                horse = horse || Horse::check_jump(board, sx - 2, sy + 1);
-                horse = horse || Horse::check_jump(board, sx - 1, sy + 2);
-                horse = horse || Horse::check_jump(board, sx + 1, sy + 2);
-                horse = horse || Horse::check_jump(board, sx + 2, sy + 1);
-                horse = horse || Horse::check_jump(board, sx + 2, sy - 1);
-                horse = horse || Horse::check_jump(board, sx + 1, sy - 2);
+                let horse = KNIGHT_JUMPS
+                    .iter()
+                    .any(|(dx, dy)| Horse::check_jump(board, sx + *dx as i32, sy + *dy as i32));
 
                 if horse {
                     1.0
@@ -15,8 +15,8 @@ impl HeuristicImplementation for PrioritizeCenter {
             "PrioritizeCenter",
             self.weigth,
             Box::new(|board, x, y| {
-                let mid = board.get_cols() / 2;
-                if x == &mid && y == &mid {
+                let (mid_x, mid_y) = (board.get_cols() / 2, board.get_rows() / 2);
+                if x == &mid_x && y == &mid_y {
                     1.0
                 } else {
                     0.0
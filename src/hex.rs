@@ -0,0 +1,427 @@
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::cell::CellContent;
+use crate::piece::Piece;
+use std::fmt;
+
+/// Transforms of the regular hexagon, the dihedral group D6
+///
+/// Acts on axial coordinates `(q, r)` centered on the middle cell. The optional reflection
+/// swaps `q` and `r` and is applied before the counterclockwise turns of 60 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexSymmetry {
+    rotation: u8,
+    reflection: bool,
+}
+
+impl HexSymmetry {
+    /// Every transform, the rotations first
+    pub const ALL: [HexSymmetry; 12] = [
+        HexSymmetry::new(0, false),
+        HexSymmetry::new(1, false),
+        HexSymmetry::new(2, false),
+        HexSymmetry::new(3, false),
+        HexSymmetry::new(4, false),
+        HexSymmetry::new(5, false),
+        HexSymmetry::new(0, true),
+        HexSymmetry::new(1, true),
+        HexSymmetry::new(2, true),
+        HexSymmetry::new(3, true),
+        HexSymmetry::new(4, true),
+        HexSymmetry::new(5, true),
+    ];
+
+    /// Reflection, if any, followed by `rotation` turns of 60 degrees
+    pub const fn new(rotation: u8, reflection: bool) -> Self {
+        HexSymmetry {
+            rotation: rotation % 6,
+            reflection,
+        }
+    }
+
+    pub fn get_rotation(&self) -> &u8 {
+        &self.rotation
+    }
+
+    pub fn is_reflection(&self) -> bool {
+        self.reflection
+    }
+
+    /// Map the axial coordinates of a cell
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::hex::HexSymmetry;
+    ///
+    /// assert_eq!(HexSymmetry::new(1, false).apply(1, 0), (0, 1));
+    /// assert_eq!(HexSymmetry::new(3, false).apply(2, -1), (-2, 1));
+    /// assert_eq!(HexSymmetry::new(0, true).apply(2, -1), (-1, 2));
+    /// ```
+    pub fn apply(&self, q: i64, r: i64) -> (i64, i64) {
+        let (mut q, mut r) = if self.reflection { (r, q) } else { (q, r) };
+        for _ in 0..self.rotation {
+            let turned = (-r, q + r);
+            q = turned.0;
+            r = turned.1;
+        }
+        (q, r)
+    }
+}
+
+/// Hexagonal board of side `n`, where queens attack along the three hex axes
+///
+/// Cells have axial coordinates `(q, r)` with `|q|`, `|r|` and `|q + r|` below `n`, the
+/// middle cell being `(0, 0)`. Queens attack along the lines of constant `q`, constant `r`
+/// and constant `q + r`.
+///
+/// The cells are laid on a square `Board` of side `2n - 1`, with the two corners outside of
+/// the hexagon blocked by holes, so the `Solver` and its heuristics work on `get_mut_board`.
+/// The solver only prunes with the transforms shared by the hexagon and the square, while
+/// the signatures of this board use the whole group D6.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::hex::HexBoard;
+/// use andaluz_core::solver::Solver;
+///
+/// let mut board = HexBoard::new(3);
+///
+/// let mut solver = Solver::new();
+/// assert!(solver.solve(board.get_mut_board()).unwrap().is_solved());
+///
+/// assert_eq!(board.get_queens().len(), 5);
+/// assert!(board.is_solved());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexBoard {
+    side: usize,
+    board: Board,
+}
+
+impl HexBoard {
+    /// Constructor of a board with `side` cells along each edge
+    pub fn new(side: usize) -> Self {
+        let width = (2 * side).saturating_sub(1);
+        let mut board = Board::new(width);
+        board
+            .set_piece(Piece::hex_queen())
+            .expect("An empty board accepts any piece");
+
+        let mut hex = HexBoard { side, board };
+        for y in 1..=width {
+            for x in 1..=width {
+                let (q, r) = hex.get_qr_from_xy(&x, &y);
+                if !hex.contains(q, r) {
+                    hex.board
+                        .toggle_hole(&x, &y)
+                        .expect("Cells of an empty board can be blocked");
+                }
+            }
+        }
+
+        hex
+    }
+
+    pub fn get_side(&self) -> &usize {
+        &self.side
+    }
+
+    /// Square board holding the hexagon
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Square board holding the hexagon, to be solved by a `Solver`
+    ///
+    /// Only the queens should be changed, the holes are part of the shape of the hexagon.
+    pub fn get_mut_board(&mut self) -> &mut Board {
+        &mut self.board
+    }
+
+    /// Whether the axial coordinates are inside the hexagon
+    pub fn contains(&self, q: i64, r: i64) -> bool {
+        let limit = self.side as i64;
+        q.abs() < limit && r.abs() < limit && (q + r).abs() < limit
+    }
+
+    /// Cell of the square board holding `(q, r)`
+    fn get_xy_from_qr(&self, q: i64, r: i64) -> Result<(usize, usize), String> {
+        if !self.contains(q, r) {
+            return Err(format!("Cell ({}, {}) out of the hexagon", q, r));
+        }
+        let side = self.side as i64;
+        Ok(((q + side) as usize, (r + side) as usize))
+    }
+
+    fn get_qr_from_xy(&self, x: &usize, y: &usize) -> (i64, i64) {
+        let side = self.side as i64;
+        (*x as i64 - side, *y as i64 - side)
+    }
+
+    /// Axial coordinates of every cell, by increasing `r` then `q`
+    pub fn get_coords(&self) -> Vec<(i64, i64)> {
+        let limit = self.side as i64;
+        (1 - limit..limit)
+            .flat_map(|r| (1 - limit..limit).map(move |q| (q, r)))
+            .filter(|(q, r)| self.contains(*q, *r))
+            .collect()
+    }
+
+    pub fn get_cell_content(&self, q: i64, r: i64) -> Result<&CellContent, String> {
+        let (x, y) = self.get_xy_from_qr(q, r)?;
+        self.board.get_cell_content(&x, &y)
+    }
+
+    /// Put or remove a queen from a cell
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::cell::CellContent;
+    /// use andaluz_core::hex::HexBoard;
+    ///
+    /// let mut board = HexBoard::new(3);
+    ///
+    /// board.toggle_cell(0, 0).unwrap();
+    ///
+    /// assert_eq!(board.get_cell_content(2, -2).unwrap(), &CellContent::Attack(1));
+    /// assert_eq!(board.get_cell_content(1, 1).unwrap(), &CellContent::Empty);
+    /// assert!(board.toggle_cell(2, 1).is_err());
+    /// ```
+    pub fn toggle_cell(&mut self, q: i64, r: i64) -> Result<(), String> {
+        let (x, y) = self.get_xy_from_qr(q, r)?;
+        self.board.toggle_cell(&x, &y)
+    }
+
+    /// Axial coordinates of the placed queens
+    pub fn get_queens(&self) -> Vec<(i64, i64)> {
+        self.board
+            .get_queens()
+            .iter()
+            .map(|(x, y)| self.get_qr_from_xy(x, y))
+            .collect()
+    }
+
+    /// Number of queens of a complete solution, one per row by default
+    pub fn get_goal(&self) -> usize {
+        self.board.get_goal()
+    }
+
+    pub fn set_goal(&mut self, goal: usize) {
+        self.board.set_goal(goal);
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    /// Queen bits of the cells of the hexagon only, packed MSB-first in `get_coords` order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::hex::HexBoard;
+    ///
+    /// let mut board = HexBoard::new(2);
+    /// board.toggle_cell(0, -1).unwrap();
+    ///
+    /// assert_eq!(board.get_signature(), vec![0x80]);
+    /// assert_eq!(board.to_string(), "1000000");
+    /// ```
+    pub fn get_signature(&self) -> Vec<u8> {
+        let mut bits = BitBoard::new(self.get_coords().len());
+        for (i, (q, r)) in self.get_coords().into_iter().enumerate() {
+            if self
+                .get_cell_content(q, r)
+                .map(|c| c == &CellContent::Queen)
+                == Ok(true)
+            {
+                bits.set(i);
+            }
+        }
+        bits.to_signature()
+    }
+
+    /// Build a board from its side and signature
+    pub fn from_signature(side: usize, signature: &[u8]) -> Result<HexBoard, String> {
+        let mut board = HexBoard::new(side);
+
+        let coords = board.get_coords();
+        for i in BitBoard::from_signature(coords.len(), signature).iter_ones() {
+            let (q, r) = coords[i];
+            board.toggle_cell(q, r)?;
+        }
+
+        Ok(board)
+    }
+
+    /// Copy of the board with the queens moved by a transform
+    pub fn transform(&self, symmetry: &HexSymmetry) -> Result<HexBoard, String> {
+        let mut board = HexBoard::new(self.side);
+        board.board.set_goal(self.get_goal());
+
+        for (q, r) in self.get_queens() {
+            let (tq, tr) = symmetry.apply(q, r);
+            board.toggle_cell(tq, tr)?;
+        }
+
+        Ok(board)
+    }
+
+    /// Signatures of the board moved by each transform of `HexSymmetry::ALL`
+    pub fn get_equivalent_signatures(&self) -> Result<Vec<Vec<u8>>, String> {
+        HexSymmetry::ALL
+            .iter()
+            .map(|s| Ok(self.transform(s)?.get_signature()))
+            .collect()
+    }
+
+    /// Lexicographically smallest signature of the orbit of the board
+    pub fn get_canonical_signature(&self) -> Result<Vec<u8>, String> {
+        Ok(self
+            .get_equivalent_signatures()?
+            .into_iter()
+            .min()
+            .unwrap_or_default())
+    }
+
+    /// Transforms that map the board onto itself
+    pub fn get_stabilizer(&self) -> Vec<HexSymmetry> {
+        let queens = self.get_queens();
+
+        HexSymmetry::ALL
+            .iter()
+            .filter(|s| {
+                queens.iter().all(|(q, r)| {
+                    let (tq, tr) = s.apply(*q, *r);
+                    self.get_cell_content(tq, tr) == Ok(&CellContent::Queen)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Rows of the hexagon with the cells staggered, the row `r = n - 1` on top
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::hex::HexBoard;
+    ///
+    /// let mut board = HexBoard::new(2);
+    /// board.toggle_cell(1, 0).unwrap();
+    ///
+    /// assert_eq!(board.to_multiline_string(), " 0 0\n0 0 1\n 0 0\n");
+    /// ```
+    pub fn to_multiline_string(&self) -> String {
+        let limit = self.side as i64;
+
+        (1 - limit..limit)
+            .rev()
+            .map(|r| {
+                let row = (1 - limit..limit)
+                    .filter(|q| self.contains(*q, r))
+                    .map(|q| match self.get_cell_content(q, r) {
+                        Ok(CellContent::Queen) => "1",
+                        _ => "0",
+                    })
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                format!("{}{}\n", " ".repeat(r.unsigned_abs() as usize), row)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for HexBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let board = self
+            .get_coords()
+            .into_iter()
+            .map(|(q, r)| match self.get_cell_content(q, r) {
+                Ok(CellContent::Queen) => '1',
+                _ => '0',
+            })
+            .collect::<String>();
+        write!(f, "{}", board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use std::collections::HashSet;
+
+    #[test]
+    fn hex_attacks() {
+        let mut board = HexBoard::new(3);
+        assert_eq!(board.get_coords().len(), 19);
+
+        // The middle queen sees the 4 other cells of each of its 3 lines
+        board.toggle_cell(0, 0).unwrap();
+        let attacked = board
+            .get_coords()
+            .into_iter()
+            .filter(|(q, r)| board.get_cell_content(*q, *r).unwrap() != &CellContent::Empty)
+            .count();
+        assert_eq!(attacked, 1 + 3 * 4);
+        assert_eq!(board.get_board().get_available_cells().len(), 6);
+    }
+
+    #[test]
+    fn d6_group() {
+        // The group is closed and acts on the hexagon
+        let board = HexBoard::new(4);
+        let cells = board.get_coords().into_iter().collect::<HashSet<_>>();
+        for s in HexSymmetry::ALL.iter() {
+            assert!(cells.iter().all(|(q, r)| {
+                let t = s.apply(*q, *r);
+                cells.contains(&t)
+            }));
+            for t in HexSymmetry::ALL.iter() {
+                let composed = HexSymmetry::ALL.iter().find(|u| {
+                    cells.iter().all(|(q, r)| {
+                        u.apply(*q, *r) == t.apply(s.apply(*q, *r).0, s.apply(*q, *r).1)
+                    })
+                });
+                assert!(composed.is_some());
+            }
+        }
+
+        // Rotations by 60 degrees have order 6
+        let turn = HexSymmetry::new(1, false);
+        let (mut q, mut r) = (2, -1);
+        for k in 1..=6 {
+            let next = turn.apply(q, r);
+            q = next.0;
+            r = next.1;
+            assert_eq!((q, r) == (2, -1), k == 6);
+        }
+    }
+
+    #[test]
+    fn solve_hexagons() {
+        // 2n - 1 queens, one per row, fit on every side tried
+        for side in 1..=5 {
+            let mut board = HexBoard::new(side);
+            let mut solver = Solver::new();
+            assert!(solver.solve(board.get_mut_board()).unwrap().is_solved());
+            assert_eq!(board.get_queens().len(), 2 * side - 1);
+
+            let copy = HexBoard::from_signature(side, &board.get_signature()).unwrap();
+            assert_eq!(copy.get_queens(), board.get_queens());
+
+            let canonical = board.get_canonical_signature().unwrap();
+            let orbit = board
+                .get_equivalent_signatures()
+                .unwrap()
+                .into_iter()
+                .collect::<HashSet<_>>();
+            assert!(orbit.contains(&canonical));
+            assert_eq!(orbit.len() * board.get_stabilizer().len(), 12);
+        }
+    }
+}
@@ -1,6 +1,25 @@
+pub mod betza;
+pub mod bitboard;
 pub mod board;
+pub mod board_nd;
 pub mod cell;
 pub mod classifier;
+pub mod cnf;
+pub mod collinear;
+pub mod coloring;
+pub mod constraint;
+pub mod construction;
+pub mod counter;
+pub mod goal;
+pub mod graph;
 pub mod heuristic;
 pub mod heuristic_implementation;
+pub mod hex;
+pub mod local_search;
+pub mod piece;
+pub mod region;
+pub mod rng;
+pub mod sat;
 pub mod solver;
+pub mod symmetry;
+pub mod topology;
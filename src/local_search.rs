@@ -0,0 +1,381 @@
+use crate::board::Board;
+use crate::rng::Rng;
+use crate::solver::SolverResult;
+
+/// Largest board packed into a signature or a `Board`, bigger results stay permutations
+pub const MAX_BOARD_COLS: usize = 1024;
+
+/// Collision counters of the diagonals of a permutation
+///
+/// Rows and columns never collide in a permutation, so only the diagonals are tracked.
+struct Diagonals {
+    cols: usize,
+    diagonals: Vec<u32>,
+    anti_diagonals: Vec<u32>,
+    collisions: usize,
+}
+
+impl Diagonals {
+    fn new(cols: usize) -> Self {
+        let lines = (2 * cols).saturating_sub(1);
+        Diagonals {
+            cols,
+            diagonals: vec![0; lines],
+            anti_diagonals: vec![0; lines],
+            collisions: 0,
+        }
+    }
+
+    fn lines(&self, row: usize, col: usize) -> (usize, usize) {
+        (row + col, row + self.cols - 1 - col)
+    }
+
+    fn is_free(&self, row: usize, col: usize) -> bool {
+        let (d, a) = self.lines(row, col);
+        self.diagonals[d] == 0 && self.anti_diagonals[a] == 0
+    }
+
+    fn is_attacked(&self, row: usize, col: usize) -> bool {
+        let (d, a) = self.lines(row, col);
+        self.diagonals[d] > 1 || self.anti_diagonals[a] > 1
+    }
+
+    fn add(&mut self, row: usize, col: usize) {
+        let (d, a) = self.lines(row, col);
+        for count in [&mut self.diagonals[d], &mut self.anti_diagonals[a]] {
+            if *count > 0 {
+                self.collisions += 1;
+            }
+            *count += 1;
+        }
+    }
+
+    fn remove(&mut self, row: usize, col: usize) {
+        let (d, a) = self.lines(row, col);
+        for count in [&mut self.diagonals[d], &mut self.anti_diagonals[a]] {
+            *count -= 1;
+            if *count > 0 {
+                self.collisions -= 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalSearchResult {
+    cols: usize,
+    seed: u64,
+    iterations: u64,
+    restarts: u32,
+    permutation: Option<Vec<usize>>,
+}
+
+impl LocalSearchResult {
+    pub fn get_cols(&self) -> &usize {
+        &self.cols
+    }
+
+    pub fn get_seed(&self) -> &u64 {
+        &self.seed
+    }
+
+    /// Swaps attempted while repairing the initial permutations
+    pub fn get_iterations(&self) -> &u64 {
+        &self.iterations
+    }
+
+    pub fn get_restarts(&self) -> &u32 {
+        &self.restarts
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.permutation.is_some()
+    }
+
+    /// Column of the queen of each row, starting at 1 like the board coordinates
+    pub fn get_permutation(&self) -> &Option<Vec<usize>> {
+        &self.permutation
+    }
+
+    pub fn get_description(&self) -> String {
+        format!("MinConflicts(seed {})", self.seed)
+    }
+
+    /// Fail for boards too big to hold every cell, only available through `get_permutation`
+    fn check_size(&self) -> Result<(), String> {
+        if self.cols > MAX_BOARD_COLS {
+            return Err(format!(
+                "Boards with more than {} columns are only available as permutations, see \
+                 get_permutation",
+                MAX_BOARD_COLS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Signature of the solved board, packed from the permutation
+    pub fn get_signature(&self) -> Result<Vec<u8>, String> {
+        self.check_size()?;
+        match &self.permutation {
+            Some(p) => Ok(Board::permutation_signature(p)),
+            None => Err("No solution found".to_string()),
+        }
+    }
+
+    pub fn to_board(&self) -> Result<Board, String> {
+        self.check_size()?;
+        match &self.permutation {
+            Some(p) => Board::from_permutation(p),
+            None => Err("No solution found".to_string()),
+        }
+    }
+
+    /// Report the outcome the same way the backtracking `Solver` does
+    pub fn to_solver_result(&self) -> Result<SolverResult, String> {
+        self.check_size()?;
+
+        let empty = vec![0_u8; (self.cols * self.cols).div_ceil(8)];
+        let mut result = SolverResult::new(empty, self.get_description());
+        result.set_jumps(self.iterations.min(u32::MAX as u64) as u32);
+
+        if self.is_solved() {
+            result.set_solved(self.get_signature()?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Min-conflicts local search over permutations, after Sosič and Gu
+///
+/// A random permutation is built greedily avoiding diagonal collisions, then queens under
+/// attack are swapped with random partners whenever the swap lowers the number of collisions.
+/// When a whole pass makes no progress the search restarts from a new permutation.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::local_search::MinConflicts;
+///
+/// let mut solver = MinConflicts::new();
+/// solver.set_seed(42);
+///
+/// let result = solver.solve(500);
+///
+/// assert!(result.is_solved());
+/// assert_eq!(result.get_permutation().as_ref().unwrap().len(), 500);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinConflicts {
+    max_iterations: u64,
+    seed: u64,
+}
+
+impl MinConflicts {
+    pub fn new() -> Self {
+        MinConflicts {
+            max_iterations: 100000,
+            seed: 0,
+        }
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: u64) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn solve(&self, cols: usize) -> LocalSearchResult {
+        let mut rng = Rng::new(self.seed);
+        let mut result = LocalSearchResult {
+            cols,
+            seed: self.seed,
+            iterations: 0,
+            restarts: 0,
+            permutation: None,
+        };
+
+        loop {
+            let (mut queens, mut diagonals) = MinConflicts::initial_permutation(cols, &mut rng);
+
+            if self.repair(
+                &mut queens,
+                &mut diagonals,
+                &mut rng,
+                &mut result.iterations,
+            ) {
+                result.permutation = Some(queens.iter().map(|c| c + 1).collect());
+                return result;
+            }
+
+            if result.iterations >= self.max_iterations {
+                return result;
+            }
+
+            result.restarts += 1;
+        }
+    }
+
+    fn initial_permutation(cols: usize, rng: &mut Rng) -> (Vec<usize>, Diagonals) {
+        let mut queens = (0..cols).collect::<Vec<usize>>();
+        let mut diagonals = Diagonals::new(cols);
+
+        let max_attempts = (3.08 * cols as f64) as usize;
+        let mut attempts = 0;
+        let mut row = 0;
+
+        while row < cols && attempts < max_attempts {
+            let m = row + rng.below(cols - row);
+            queens.swap(row, m);
+            attempts += 1;
+
+            if diagonals.is_free(row, queens[row]) {
+                diagonals.add(row, queens[row]);
+                row += 1;
+            }
+        }
+
+        for r in row..cols {
+            let m = r + rng.below(cols - r);
+            queens.swap(r, m);
+            diagonals.add(r, queens[r]);
+        }
+
+        (queens, diagonals)
+    }
+
+    fn repair(
+        &self,
+        queens: &mut [usize],
+        diagonals: &mut Diagonals,
+        rng: &mut Rng,
+        iterations: &mut u64,
+    ) -> bool {
+        let cols = queens.len();
+
+        while diagonals.collisions > 0 {
+            let mut swaps = 0;
+
+            for i in 0..cols {
+                if !diagonals.is_attacked(i, queens[i]) {
+                    continue;
+                }
+
+                for _ in 0..cols {
+                    if *iterations >= self.max_iterations {
+                        return false;
+                    }
+                    *iterations += 1;
+
+                    let j = rng.below(cols);
+                    if j == i {
+                        continue;
+                    }
+
+                    let before = diagonals.collisions;
+                    MinConflicts::swap(queens, diagonals, i, j);
+
+                    if diagonals.collisions < before {
+                        swaps += 1;
+                        break;
+                    }
+
+                    MinConflicts::swap(queens, diagonals, i, j);
+                }
+            }
+
+            if swaps == 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn swap(queens: &mut [usize], diagonals: &mut Diagonals, i: usize, j: usize) {
+        diagonals.remove(i, queens[i]);
+        diagonals.remove(j, queens[j]);
+        queens.swap(i, j);
+        diagonals.add(i, queens[i]);
+        diagonals.add(j, queens[j]);
+    }
+}
+
+impl Default for MinConflicts {
+    fn default() -> Self {
+        MinConflicts::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_valid(permutation: &[usize]) -> bool {
+        let cols = permutation.len();
+        let mut seen = vec![false; cols];
+        let mut diagonals = Diagonals::new(cols);
+
+        for (row, col) in permutation.iter().enumerate() {
+            if seen[col - 1] || !diagonals.is_free(row, col - 1) {
+                return false;
+            }
+            seen[col - 1] = true;
+            diagonals.add(row, col - 1);
+        }
+
+        true
+    }
+
+    #[test]
+    fn solves_boards() {
+        for cols in [1, 4, 5, 8, 13, 100, 20000].iter() {
+            let mut solver = MinConflicts::new();
+            solver.set_seed(*cols as u64);
+
+            let result = solver.solve(*cols);
+            assert!(result.is_solved(), "{} columns", cols);
+            assert!(is_valid(result.get_permutation().as_ref().unwrap()));
+        }
+    }
+
+    #[test]
+    fn exhausts_budget() {
+        let mut solver = MinConflicts::new();
+        solver.set_max_iterations(500);
+
+        let result = solver.solve(3);
+        assert!(!result.is_solved());
+        assert_eq!(result.get_iterations(), &500);
+    }
+
+    #[test]
+    fn deterministic_seed() {
+        let mut solver = MinConflicts::new();
+        solver.set_seed(3);
+
+        let a = solver.solve(64);
+        let b = solver.solve(64);
+        assert_eq!(a.get_permutation(), b.get_permutation());
+    }
+
+    #[test]
+    fn solver_result() {
+        let result = MinConflicts::new().solve(10);
+        let solver_result = result.to_solver_result().unwrap();
+
+        assert!(solver_result.is_solved());
+
+        let board = Board::from(solver_result.get_solution().clone().unwrap());
+        assert!(board.is_solved());
+
+        // Big boards are only given as permutations
+        let result = MinConflicts::new().solve(MAX_BOARD_COLS + 1);
+        assert!(result.is_solved());
+        assert!(result.to_solver_result().is_err());
+        assert!(result.get_signature().is_err());
+        assert!(result.to_board().is_err());
+    }
+}
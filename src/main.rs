@@ -1,15 +1,33 @@
 use andaluz_core::board::Board;
+use andaluz_core::board_nd::BoardND;
+use andaluz_core::cell::Color;
+use andaluz_core::cnf::{decode, encode, parse_model, Encoding};
+use andaluz_core::coloring::Coloring;
+use andaluz_core::constraint::{self, Constraint};
+use andaluz_core::construction::construct_solution;
+use andaluz_core::counter::{count_no_three_in_line, count_solutions, count_toroidal_solutions};
+use andaluz_core::graph::AttackGraph;
 use andaluz_core::heuristic_implementation::horse::Horse;
 use andaluz_core::heuristic_implementation::prioritizecenter::PrioritizeCenter;
 use andaluz_core::heuristic_implementation::HeuristicImplementation;
+use andaluz_core::hex::HexBoard;
+use andaluz_core::local_search::{MinConflicts, MAX_BOARD_COLS};
+use andaluz_core::piece::Piece;
+use andaluz_core::region::RegionBoard;
 use andaluz_core::solver::Solver;
-use clap::{App, Arg};
+use andaluz_core::topology::Topology;
+use clap::{App, Arg, ArgMatches};
+use std::fmt::Display;
+use std::io::{self, Write};
 
 const NAME: Option<&'static str> = option_env!("CARGO_PKG_NAME");
 const VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
 const AUTHORS: Option<&'static str> = option_env!("CARGO_PKG_AUTHORS");
 const DESCRIPTION: Option<&'static str> = option_env!("CARGO_PKG_DESCRIPTION");
 
+/// Boards bigger than this are solved with the explicit construction instead of backtracking
+const MAX_BACKTRACK_COLS: usize = 32;
+
 fn main() {
     let matches = App::new(NAME.unwrap())
         .version(VERSION.unwrap())
@@ -20,7 +38,7 @@ fn main() {
                 .short("f")
                 .long("format")
                 .value_name("FORMAT")
-                .help("Set the i/o format")
+                .help("Set the i/o format, boards over 1024 columns are printed as permutations")
                 .takes_value(true)
                 .possible_values(&["bits", "decimal", "hex", "pretty"])
                 .default_value("pretty"),
@@ -34,6 +52,179 @@ fn main() {
                 .takes_value(true)
                 .default_value("8"),
         )
+        .arg(
+            Arg::with_name("rows")
+                .short("r")
+                .long("rows")
+                .value_name("ROWS")
+                .help("Set the number of rows, defaults to the number of columns")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("holes")
+                .long("holes")
+                .value_name("HOLES")
+                .help("Set the blocked cells, as x,y pairs separated by semicolons")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("obstacles")
+                .long("obstacles")
+                .value_name("OBSTACLES")
+                .help("Set the cells that stop attacks, as x,y pairs separated by semicolons")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("dimensions")
+                .short("d")
+                .long("dimensions")
+                .value_name("DIMENSIONS")
+                .help("Set the number of dimensions of a board with the columns as side")
+                .takes_value(true)
+                .default_value("2"),
+        )
+        .arg(
+            Arg::with_name("hexagon")
+                .long("hexagon")
+                .help("Use a hexagonal board with the columns as side, queens attacking along the hex axes"),
+        )
+        .arg(
+            Arg::with_name("regions")
+                .long("regions")
+                .value_name("REGIONS")
+                .help("Solve a region puzzle, given as rows of region letters separated by semicolons")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("stars")
+                .long("stars")
+                .value_name("STARS")
+                .help("Set the number of stars per row, column and region of a region puzzle")
+                .takes_value(true)
+                .default_value("1"),
+        )
+        .arg(
+            Arg::with_name("piece")
+                .short("p")
+                .long("piece")
+                .value_name("PIECE")
+                .help("Set the piece to place, by name such as knight or in Betza notation such as WB")
+                .takes_value(true)
+                .default_value("queen"),
+        )
+        .arg(
+            Arg::with_name("mixture")
+                .long("mixture")
+                .value_name("MIXTURE")
+                .help("Place several kinds of piece, as piece:count pairs separated by semicolons")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("goal")
+                .short("g")
+                .long("goal")
+                .value_name("GOAL")
+                .help("Set the number of pieces to place, defaults to the smallest side")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("constraint")
+                .long("constraint")
+                .value_name("CONSTRAINT")
+                .help("Add a side rule on where pieces may go, may be repeated")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&[
+                    "no-three-in-line",
+                    "no-knight-move",
+                    "no-long-diagonals",
+                    "dark-squares",
+                ]),
+        )
+        .arg(
+            Arg::with_name("no_three_in_line")
+                .long("no-three-in-line")
+                .help("Keep any three queens off a straight line of any slope, one queen per row"),
+        )
+        .arg(
+            Arg::with_name("peaceable")
+                .long("peaceable")
+                .help("Place two armies as large as possible, no piece attacking the other side"),
+        )
+        .arg(
+            Arg::with_name("weights")
+                .long("weights")
+                .value_name("WEIGHTS")
+                .help("Find the heaviest solution, given rows of comma separated cell weights separated by semicolons, the top row first")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("dominate")
+                .long("dominate")
+                .help("Place the fewest pieces attacking every empty cell"),
+        )
+        .arg(
+            Arg::with_name("independent")
+                .long("independent")
+                .requires("dominate")
+                .help("Keep the dominating pieces from attacking each other"),
+        )
+        .arg(
+            Arg::with_name("coloring")
+                .long("coloring")
+                .help("Color the cells with the fewest colors, each color a set of non-attacking pieces"),
+        )
+        .arg(
+            Arg::with_name("export")
+                .long("export")
+                .value_name("EXPORT")
+                .help("Print the attack graph or the DIMACS CNF formula of the board instead of solving it")
+                .takes_value(true)
+                .possible_values(&["dot", "graphml", "edges", "dimacs"]),
+        )
+        .arg(
+            Arg::with_name("encoding")
+                .long("encoding")
+                .value_name("ENCODING")
+                .help("Set the at-most-one encoding of the DIMACS export and the SAT strategy, defaults to pairwise")
+                .takes_value(true)
+                .possible_values(&["pairwise", "sequential", "commander"]),
+        )
+        .arg(
+            Arg::with_name("model")
+                .long("model")
+                .value_name("MODEL")
+                .help("Print the board of a SAT solver model, read from a file, of the DIMACS export")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("placed")
+                .long("placed")
+                .value_name("PLACED")
+                .help("Place pieces before exporting, as x,y pairs separated by semicolons, graphs keeping only the cells left available")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("maximize")
+                .long("maximize")
+                .help("Place as many pieces as possible instead of one per column"),
+        )
+        .arg(
+            Arg::with_name("topology")
+                .short("t")
+                .long("topology")
+                .value_name("TOPOLOGY")
+                .help("Set how the edges of the board are glued")
+                .takes_value(true)
+                .possible_values(&["plane", "cylinder", "torus", "klein"])
+                .default_value("plane"),
+        )
+        .arg(
+            Arg::with_name("count")
+                .long("count")
+                .help("Count every solution instead of solving a single board"),
+        )
         .arg(
             Arg::with_name("max_jumps")
                 .short("m")
@@ -43,6 +234,24 @@ fn main() {
                 .takes_value(true)
                 .default_value("100000"),
         )
+        .arg(
+            Arg::with_name("strategy")
+                .short("s")
+                .long("strategy")
+                .value_name("STRATEGY")
+                .help("Set the search strategy")
+                .takes_value(true)
+                .possible_values(&["backtrack", "min-conflicts", "constructive", "sat"])
+                .default_value("backtrack"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("SEED")
+                .help("Set the random seed of the min-conflicts strategy")
+                .takes_value(true)
+                .default_value("0"),
+        )
         .get_matches();
 
     let cols: usize = matches
@@ -50,64 +259,983 @@ fn main() {
         .expect("No valid columns value found!")
         .parse()
         .expect("Invalid columns value!");
-    let mut board = Board::new(cols);
-    let original = board.clone();
+    let rows: usize = match matches.value_of("rows") {
+        Some(r) => r.parse().expect("Invalid rows value!"),
+        None => cols,
+    };
+
+    let holes = match matches.value_of("holes") {
+        Some(h) => parse_cells(h),
+        None => vec![],
+    };
+
+    let obstacles = match matches.value_of("obstacles") {
+        Some(o) => parse_cells(o),
+        None => vec![],
+    };
+
+    let piece = matches.value_of("piece").expect("No valid piece found!");
+    let piece = Piece::from_name(piece)
+        .or_else(|_| Piece::from_betza(piece))
+        .unwrap_or_else(|e| panic!("Invalid piece provided! {}", e));
+
+    let dimensions: usize = matches
+        .value_of("dimensions")
+        .expect("No valid dimensions value found!")
+        .parse()
+        .expect("Invalid dimensions value!");
+
+    let mixture = match matches.value_of("mixture") {
+        Some(m) => parse_mixture(m),
+        None => vec![],
+    };
+
+    let constraints: Vec<Box<dyn Constraint>> = matches
+        .values_of("constraint")
+        .map(|c| c.map(|name| constraint::from_name(name).unwrap()).collect())
+        .unwrap_or_default();
+
+    let weights = matches.value_of("weights").map(parse_weights);
+
+    let goal: Option<usize> = matches
+        .value_of("goal")
+        .map(|g| g.parse().expect("Invalid goal value!"));
+
+    let topology = Topology::from_name(
+        matches
+            .value_of("topology")
+            .expect("No valid topology found!"),
+    )
+    .expect("Invalid topology provided!");
+
+    // The SAT strategy encodes the same boards as the backtracking, one piece per row
+    let searches_board = matches!(
+        matches.value_of("strategy"),
+        Some("backtrack") | Some("sat")
+    );
+
+    if matches.value_of("strategy") == Some("sat")
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || matches.is_present("regions")
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || matches.is_present("count")
+            || matches.is_present("maximize"))
+    {
+        panic!("The SAT strategy only solves boards with one piece per row!");
+    }
+
+    if rows != cols && (matches.is_present("count") || !searches_board) {
+        panic!("Rectangular boards are only supported by the backtrack and SAT strategies!");
+    }
+
+    if !holes.is_empty() && (matches.is_present("count") || !searches_board) {
+        panic!("Boards with holes are only supported by the backtrack and SAT strategies!");
+    }
+
+    if !mixture.is_empty()
+        && (goal.is_some()
+            || matches.is_present("maximize")
+            || matches.is_present("hexagon")
+            || dimensions != 2)
+    {
+        panic!("Mixtures of pieces can't be combined with goals, hexagons or dimensions!");
+    }
+
+    if (!obstacles.is_empty()
+        || !piece.is_queen()
+        || !mixture.is_empty()
+        || !constraints.is_empty()
+        || goal.is_some()
+        || matches.is_present("maximize"))
+        && (matches.is_present("count") || !searches_board)
+    {
+        panic!(
+            "Obstacles, pieces and goals are only supported by the backtrack and SAT strategies!"
+        );
+    }
+
+    if topology != Topology::Plane
+        && (!searches_board || (matches.is_present("count") && topology != Topology::Torus))
+    {
+        panic!("Glued boards are only supported by the backtrack and SAT strategies and toroidal counts!");
+    }
+
+    if dimensions != 2
+        && (rows != cols
+            || !holes.is_empty()
+            || !obstacles.is_empty()
+            || !piece.is_queen()
+            || topology != Topology::Plane
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Boards with other than 2 dimensions only support queens, goals and backtracking!");
+    }
+
+    if (matches.is_present("hexagon") || dimensions != 2) && !constraints.is_empty() {
+        panic!("Constraints are only supported on square and rectangular boards!");
+    }
+
+    if matches.is_present("hexagon")
+        && (dimensions != 2
+            || rows != cols
+            || !holes.is_empty()
+            || !obstacles.is_empty()
+            || !piece.is_queen()
+            || topology != Topology::Plane
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Hexagonal boards only support queens, goals and backtracking!");
+    }
+
+    if matches.is_present("no_three_in_line")
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || !piece.is_queen()
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || topology != Topology::Plane
+            || matches.is_present("maximize")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("No three in line only supports queens on plane boards with backtracking!");
+    }
+
+    if matches.is_present("regions")
+        && (matches.occurrences_of("cols") > 0
+            || matches.is_present("rows")
+            || dimensions != 2
+            || matches.is_present("hexagon")
+            || !holes.is_empty()
+            || !obstacles.is_empty()
+            || !piece.is_queen()
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || topology != Topology::Plane
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.is_present("no_three_in_line")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Region puzzles take their size from the regions and only support backtracking!");
+    }
+
+    if matches.is_present("peaceable")
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.is_present("no_three_in_line")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Peaceable armies can't be combined with goals, mixtures or other modes!");
+    }
+
+    if weights.is_some()
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.is_present("peaceable")
+            || matches.is_present("dominate")
+            || matches.is_present("no_three_in_line")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Weights can't be combined with goals, mixtures or other modes!");
+    }
+
+    if matches.is_present("dominate")
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.is_present("peaceable")
+            || matches.is_present("no_three_in_line")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Domination can't be combined with goals, mixtures or other modes!");
+    }
+
+    if matches.is_present("coloring")
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || !mixture.is_empty()
+            || !constraints.is_empty()
+            || goal.is_some()
+            || weights.is_some()
+            || matches.is_present("count")
+            || matches.is_present("maximize")
+            || matches.is_present("peaceable")
+            || matches.is_present("dominate")
+            || matches.is_present("no_three_in_line")
+            || matches.value_of("strategy") != Some("backtrack"))
+    {
+        panic!("Coloring can't be combined with goals, mixtures or other modes!");
+    }
+
+    if (matches.is_present("placed") || matches.is_present("encoding"))
+        && !matches.is_present("export")
+        && !matches.is_present("model")
+        && matches.value_of("strategy") != Some("sat")
+    {
+        panic!("Placed pieces and encodings only apply to exports, models and the SAT strategy!");
+    }
+
+    if (matches.is_present("export") || matches.is_present("model"))
+        && (dimensions != 2
+            || matches.is_present("hexagon")
+            || matches.is_present("regions")
+            || matches.is_present("count"))
+    {
+        panic!("Export only works on square and rectangular boards!");
+    }
+
+    if matches.is_present("count") {
+        let count = match topology {
+            Topology::Torus => count_toroidal_solutions(cols).unwrap(),
+            _ if matches.is_present("no_three_in_line") => count_no_three_in_line(cols).unwrap(),
+            _ => count_solutions(cols).unwrap(),
+        };
+
+        match matches
+            .value_of("format")
+            .expect("Invalid provided format!")
+        {
+            "hex" => {
+                println!(
+                    "{:x},{:x},{:x}",
+                    cols,
+                    count.get_total(),
+                    count.get_fundamental()
+                );
+            }
+            "pretty" => {
+                println!("Columns: {}", cols);
+                println!("Solutions: {}", count.get_total());
+                println!("Fundamental: {}", count.get_fundamental());
+            }
+            _ => {
+                println!("{},{},{}", cols, count.get_total(), count.get_fundamental());
+            }
+        };
+
+        return;
+    }
 
     let max_jumps: u32 = matches
         .value_of("max_jumps")
         .expect("No valid max jumps value found!")
         .parse()
         .expect("Invalid max jumps value!");
+
+    if dimensions != 2 {
+        let mut board = BoardND::new(cols, dimensions).expect("Invalid dimensions value!");
+        if let Some(g) = goal {
+            board.set_goal(g);
+        }
+        let original = board.clone();
+
+        let mut solver = Solver::new();
+        solver.set_max_jumps(max_jumps);
+        let result = solver.solve_nd(&mut board).unwrap();
+
+        if result.is_solved() {
+            print_board_nd(
+                &matches,
+                result.get_heuristics_description(),
+                result.get_jumps(),
+                &original,
+                &board,
+            );
+        }
+
+        return;
+    }
+
+    if let Some(grid) = matches.value_of("regions") {
+        let stars: usize = matches
+            .value_of("stars")
+            .expect("No valid stars value found!")
+            .parse()
+            .expect("Invalid stars value!");
+
+        let mut board = RegionBoard::from_letters(grid).expect("Invalid regions provided!");
+        board
+            .set_stars_per_region(stars)
+            .expect("Invalid stars value!");
+        let original = board.clone();
+
+        let mut solver = Solver::new();
+        solver.set_max_jumps(max_jumps);
+        let unique = solver.has_unique_solution(&board).ok();
+        let result = solver.solve_regions(&mut board).unwrap();
+
+        if result.is_solved() {
+            print_region_board(
+                &matches,
+                result.get_heuristics_description(),
+                result.get_jumps(),
+                &original,
+                &board,
+                unique,
+            );
+        }
+
+        return;
+    }
+
+    if matches.is_present("hexagon") {
+        let mut board = HexBoard::new(cols);
+        if let Some(g) = goal {
+            board.set_goal(g);
+        }
+        let original = board.clone();
+
+        let mut solver = Solver::new();
+        solver.set_max_jumps(max_jumps);
+        solver.push_heuristic(Horse::new(1.0));
+        solver.push_heuristic(PrioritizeCenter::new(1.0));
+        let result = solver.solve(board.get_mut_board()).unwrap();
+
+        if result.is_solved() {
+            print_hex_board(
+                &matches,
+                result.get_heuristics_description(),
+                result.get_jumps(),
+                &original,
+                &board,
+            );
+        }
+
+        return;
+    }
+
+    if matches.value_of("strategy") == Some("min-conflicts") {
+        let seed: u64 = matches
+            .value_of("seed")
+            .expect("No valid seed value found!")
+            .parse()
+            .expect("Invalid seed value!");
+
+        let mut solver = MinConflicts::new();
+        solver.set_max_iterations(max_jumps as u64);
+        solver.set_seed(seed);
+
+        let result = solver.solve(cols);
+        let permutation = match result.get_permutation() {
+            Some(p) => p,
+            None => return,
+        };
+
+        print_permutation(
+            &matches,
+            &result.get_description(),
+            result.get_iterations(),
+            permutation,
+        );
+
+        return;
+    }
+
+    if matches.value_of("strategy") == Some("constructive")
+        || (matches.value_of("strategy") == Some("backtrack")
+            && cols > MAX_BACKTRACK_COLS
+            && rows == cols
+            && holes.is_empty()
+            && obstacles.is_empty()
+            && piece.is_queen()
+            && mixture.is_empty()
+            && constraints.is_empty()
+            && topology == Topology::Plane
+            && goal.is_none()
+            && !matches.is_present("maximize")
+            && !matches.is_present("no_three_in_line")
+            && !matches.is_present("peaceable")
+            && !matches.is_present("dominate")
+            && !matches.is_present("coloring")
+            && !matches.is_present("export")
+            && !matches.is_present("model")
+            && weights.is_none())
+    {
+        let permutation = match construct_solution(cols) {
+            Ok(p) => p,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+
+        print_permutation(&matches, "Constructive", &0, &permutation);
+
+        return;
+    }
+
+    let mut board = Board::new_rectangle(cols, rows);
+    board.set_piece(piece).expect("Invalid piece provided!");
+    board
+        .set_topology(topology)
+        .expect("Invalid topology provided!");
+    if let Some(g) = goal {
+        board.set_goal(g);
+    }
+    for (x, y) in &holes {
+        board.toggle_hole(x, y).expect("Invalid hole provided!");
+    }
+    for (x, y) in &obstacles {
+        board
+            .toggle_obstacle(x, y)
+            .expect("Invalid obstacle provided!");
+    }
+    board
+        .set_mixture(&mixture)
+        .expect("Invalid mixture provided!");
+    if let Some(placed) = matches.value_of("placed") {
+        for (x, y) in parse_cells(placed) {
+            board.toggle_cell(&x, &y).expect("Invalid placed piece!");
+        }
+    }
+    let original = board.clone();
+
+    if let Some(path) = matches.value_of("model") {
+        let output = std::fs::read_to_string(path).expect("Unable to read the model!");
+        let model = parse_model(&output).expect("Invalid model provided!");
+        let decoded = decode(&board, &model).expect("Invalid model provided!");
+
+        print_board(&matches, "SAT model", &0, &board, &decoded);
+        return;
+    }
+
+    if matches.value_of("export") == Some("dimacs") {
+        let encoding = Encoding::from_name(matches.value_of("encoding").unwrap_or("pairwise"))
+            .expect("Invalid encoding provided!");
+        let cnf = encode(&board, &encoding).expect("Unable to encode the board!");
+
+        print!("{}", cnf.to_dimacs());
+        return;
+    }
+
+    if let Some(format) = matches.value_of("export") {
+        let graph = match matches.value_of("placed") {
+            Some(_) => AttackGraph::from_available(&board),
+            None => AttackGraph::from_board(&board).expect("Invalid board provided!"),
+        };
+
+        match format {
+            "dot" => print!("{}", graph.to_dot()),
+            "graphml" => print!("{}", graph.to_graphml()),
+            _ => print!("{}", graph.to_edge_list()),
+        };
+
+        return;
+    }
+
     let mut solver = Solver::new();
     solver.set_max_jumps(max_jumps);
 
     // TODO - Define weigths via cli interface
     solver.push_heuristic(Horse::new(1.0));
     solver.push_heuristic(PrioritizeCenter::new(1.0));
+    for c in constraints {
+        solver.push_constraint(c);
+    }
+
+    if matches.is_present("coloring") {
+        let result = solver.solve_coloring(&board).unwrap();
+        let coloring =
+            Coloring::from_signature(cols, rows, result.get_solution().as_ref().unwrap())
+                .expect("Invalid coloring found!");
+
+        let mut description = format!(
+            "{}, {} colors",
+            result.get_heuristics_description(),
+            coloring.get_color_count()
+        );
+        if !result.is_optimal() {
+            description = format!("{}, not proven optimal", description);
+        }
+
+        print_coloring(&matches, &description, result.get_jumps(), &coloring);
+        return;
+    }
 
-    let result = solver.solve(&mut board).unwrap();
+    if matches.value_of("strategy") == Some("sat") {
+        let encoding = Encoding::from_name(matches.value_of("encoding").unwrap_or("pairwise"))
+            .expect("Invalid encoding provided!");
+        let result = solver
+            .solve_sat(&mut board, &encoding)
+            .expect("Unable to encode the board!");
+
+        if result.is_solved() {
+            print_board(
+                &matches,
+                result.get_heuristics_description(),
+                result.get_jumps(),
+                &original,
+                &board,
+            );
+        } else if result.is_unsatisfiable() {
+            println!("No solution, proven after {} conflicts", result.get_jumps());
+        }
+        return;
+    }
+
+    let result = if let Some(weights) = &weights {
+        solver.solve_weighted(&mut board, weights).unwrap()
+    } else if matches.is_present("dominate") {
+        solver
+            .solve_domination(&mut board, matches.is_present("independent"))
+            .unwrap()
+    } else if matches.is_present("peaceable") {
+        solver.solve_peaceable(&mut board).unwrap()
+    } else if matches.is_present("no_three_in_line") {
+        solver.solve_no_three_in_line(&mut board).unwrap()
+    } else if matches.is_present("maximize") {
+        solver.solve_maximum(&mut board).unwrap()
+    } else {
+        solver.solve(&mut board).unwrap()
+    };
+
+    let mut description = result.get_heuristics_description().clone();
+    if let Some(value) = result.get_value() {
+        description = format!("{}, value {}", description, value);
+    }
+    if !result.is_optimal() {
+        if matches.is_present("maximize") || matches.is_present("peaceable") || weights.is_some() {
+            description = format!("{}, lower bound", description);
+        } else if matches.is_present("dominate") {
+            description = format!("{}, upper bound", description);
+        }
+    }
 
     if result.is_solved() {
-        match matches
-            .value_of("format")
-            .expect("Invalid provided format!")
-        {
-            "bits" => {
-                println!(
-                    "{},{},{}",
-                    original.to_string(),
-                    result.get_jumps(),
-                    board.to_string()
-                );
+        print_board(
+            &matches,
+            &description,
+            result.get_jumps(),
+            &original,
+            &board,
+        );
+    }
+}
+
+fn print_board(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    original: &Board,
+    board: &Board,
+) {
+    match matches
+        .value_of("format")
+        .expect("Invalid provided format!")
+    {
+        "bits" => {
+            println!("{},{},{}", original, jumps, board);
+        }
+        "decimal" if board.has_obstacles() => {
+            println!(
+                "{:?},{},{:?},{:?},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature(),
+                board.get_hole_signature(),
+                board.get_obstacle_signature()
+            );
+        }
+        "decimal" if board.has_holes() => {
+            println!(
+                "{:?},{},{:?},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature(),
+                board.get_hole_signature()
+            );
+        }
+        "decimal" => {
+            println!(
+                "{:?},{},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "hex" if board.has_obstacles() => {
+            println!(
+                "{:x?},{},{:x?},{:x?},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature(),
+                board.get_hole_signature(),
+                board.get_obstacle_signature()
+            );
+        }
+        "hex" if board.has_holes() => {
+            println!(
+                "{:x?},{},{:x?},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature(),
+                board.get_hole_signature()
+            );
+        }
+        "hex" => {
+            println!(
+                "{:x?},{},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            if matches.is_present("maximize") || matches.is_present("dominate") {
+                println!("Pieces: {}", board.get_queens().len());
             }
-            "decimal" => {
-                println!(
-                    "{:?},{},{:?}",
-                    original.get_signature(),
-                    result.get_jumps(),
-                    board.get_signature()
-                );
+            if board.is_peaceable() {
+                println!("Army: {}", board.get_army(&Color::White).len());
+                println!("White: {:?}", board.get_army(&Color::White));
+                println!("Black: {:?}", board.get_army(&Color::Black));
+            } else if !board.get_mixture().is_empty() {
+                let placement = board
+                    .get_placement()
+                    .iter()
+                    .map(|(x, y, p)| format!("{} ({}, {})", p, x, y))
+                    .collect::<Vec<String>>();
+                println!("Placement: {}", placement.join(", "));
             }
-            "hex" => {
-                println!(
-                    "{:x?},{},{:x?}",
-                    original.get_signature(),
-                    result.get_jumps(),
-                    board.get_signature()
+            println!("Signature: {:?}", board.get_signature());
+            println!("Bits: {}", board);
+            println!();
+            println!("From:");
+            println!("{}", original.to_multiline_string());
+            println!("To:");
+            println!("{}", board.to_multiline_string());
+        }
+        _ => {}
+    };
+}
+
+fn print_board_nd(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    original: &BoardND,
+    board: &BoardND,
+) {
+    match matches
+        .value_of("format")
+        .expect("Invalid provided format!")
+    {
+        "bits" => {
+            println!("{},{},{}", original, jumps, board);
+        }
+        "decimal" => {
+            println!(
+                "{:?},{},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "hex" => {
+            println!(
+                "{:x?},{},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            println!("Dimensions: {:?}", board.get_dimensions());
+            println!("Queens: {:?}", board.get_queens());
+            println!("Signature: {:?}", board.get_signature());
+            println!();
+            println!("{}", board.to_multiline_string());
+        }
+        _ => {}
+    };
+}
+
+fn print_hex_board(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    original: &HexBoard,
+    board: &HexBoard,
+) {
+    match matches
+        .value_of("format")
+        .expect("Invalid provided format!")
+    {
+        "bits" => {
+            println!("{},{},{}", original, jumps, board);
+        }
+        "decimal" => {
+            println!(
+                "{:?},{},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "hex" => {
+            println!(
+                "{:x?},{},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            println!("Queens: {:?}", board.get_queens());
+            println!("Signature: {:?}", board.get_signature());
+            println!("Canonical: {:?}", board.get_canonical_signature().unwrap());
+            println!("Bits: {}", board);
+            println!();
+            println!("{}", board.to_multiline_string());
+        }
+        _ => {}
+    };
+}
+
+fn print_coloring(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    coloring: &Coloring,
+) {
+    match matches
+        .value_of("format")
+        .expect("Invalid provided format!")
+    {
+        "bits" => {
+            println!("{},{}", jumps, coloring);
+        }
+        "decimal" => {
+            println!("{},{:?}", jumps, coloring.get_signatures());
+        }
+        "hex" => {
+            println!("{},{:x?}", jumps, coloring.get_signatures());
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            println!("Color count: {}", coloring.get_color_count());
+            println!("Solutions: {}", coloring.splits_into_solutions());
+            for (color, signature) in coloring.get_signatures().iter().enumerate() {
+                println!("Signature {}: {:?}", color, signature);
+            }
+            println!("Colors: {}", coloring);
+            println!();
+            println!("{}", coloring.to_multiline_string());
+        }
+        _ => {}
+    };
+}
+
+fn print_region_board(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    original: &RegionBoard,
+    board: &RegionBoard,
+    unique: Option<bool>,
+) {
+    match matches
+        .value_of("format")
+        .expect("Invalid provided format!")
+    {
+        "bits" => {
+            println!("{},{},{}", original, jumps, board);
+        }
+        "decimal" => {
+            println!(
+                "{:?},{},{:?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "hex" => {
+            println!(
+                "{:x?},{},{:x?}",
+                original.get_signature(),
+                jumps,
+                board.get_signature()
+            );
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            println!("Stars: {:?}", board.get_stars());
+            match unique {
+                Some(unique) => println!("Unique: {}", unique),
+                None => println!("Unique: unknown, out of jumps"),
+            }
+            println!("Signature: {:?}", board.get_signature());
+            println!("Bits: {}", board);
+            println!();
+            println!("{}", board.to_multiline_string());
+        }
+        _ => {}
+    };
+}
+
+/// Print a solution given as a permutation, never building its board
+///
+/// Up to `MAX_BOARD_COLS` columns the formats are the ones of `print_board`. Bigger boards
+/// have no signatures: the decimal format gives the columns, the jumps and the permutation
+/// instead, and the bits and hex formats are refused.
+fn print_permutation(
+    matches: &ArgMatches,
+    description: &str,
+    jumps: &impl Display,
+    permutation: &[usize],
+) {
+    let cols = permutation.len();
+    let format = matches
+        .value_of("format")
+        .expect("Invalid provided format!");
+
+    if cols > MAX_BOARD_COLS {
+        match format {
+            "bits" | "hex" => {
+                eprintln!(
+                    "Boards with more than {} columns have no {} signature, use the decimal or \
+                     pretty format",
+                    MAX_BOARD_COLS, format
                 );
+                return;
             }
-            "pretty" => {
-                println!("Heuristics: {}", result.get_heuristics_description());
-                println!("Jumps: {}", result.get_jumps());
-                println!("Signature: {:?}", board.get_signature());
-                println!("Bits: {}", board.to_string());
-                println!("");
-                println!("From:");
-                println!("{}", original.to_multiline_string());
-                println!("To:");
-                println!("{}", board.to_multiline_string());
+            "decimal" => {
+                println!("{},{},{:?}", cols, jumps, permutation);
+                return;
             }
             _ => {}
-        };
+        }
     }
+
+    match format {
+        "bits" => {
+            let bits = permutation
+                .iter()
+                .map(|x| permutation_row(cols, *x))
+                .collect::<String>();
+            println!("{},{},{}", "0".repeat(cols * cols), jumps, bits);
+        }
+        "decimal" => {
+            println!(
+                "{:?},{},{:?}",
+                vec![0_u8; (cols * cols).div_ceil(8)],
+                jumps,
+                Board::permutation_signature(permutation)
+            );
+        }
+        "hex" => {
+            println!(
+                "{:x?},{},{:x?}",
+                vec![0_u8; (cols * cols).div_ceil(8)],
+                jumps,
+                Board::permutation_signature(permutation)
+            );
+        }
+        "pretty" => {
+            println!("Heuristics: {}", description);
+            println!("Jumps: {}", jumps);
+            println!("Columns: {}", cols);
+            println!("Permutation: {:?}", permutation);
+            println!();
+
+            // One row at a time, the top row first, the board may not fit in memory
+            let stdout = io::stdout();
+            let mut out = io::BufWriter::new(stdout.lock());
+            for x in permutation.iter().rev() {
+                writeln!(out, "{}", permutation_row(cols, *x)).expect("Unable to print the board!");
+            }
+            writeln!(out).expect("Unable to print the board!");
+        }
+        _ => {}
+    };
+}
+
+/// Bits of a row of `cols` cells holding a queen on the column `x`
+fn permutation_row(cols: usize, x: usize) -> String {
+    format!("{}1{}", "0".repeat(x - 1), "0".repeat(cols - x))
+}
+
+/// Parse cell coordinates written as `x,y` pairs separated by semicolons
+fn parse_cells(cells: &str) -> Vec<(usize, usize)> {
+    cells
+        .split(';')
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| {
+            let mut xy = c.split(',').map(|v| {
+                v.trim()
+                    .parse::<usize>()
+                    .expect("Invalid cell coordinates!")
+            });
+            match (xy.next(), xy.next(), xy.next()) {
+                (Some(x), Some(y), None) => (x, y),
+                _ => panic!("Invalid cell coordinates!"),
+            }
+        })
+        .collect()
+}
+
+/// Parse the weights of the cells, written as rows of comma separated values, the top row first
+fn parse_weights(weights: &str) -> Vec<Vec<i64>> {
+    weights
+        .split(';')
+        .filter(|r| !r.trim().is_empty())
+        .rev()
+        .map(|r| {
+            r.split(',')
+                .map(|w| w.trim().parse().expect("Invalid weight provided!"))
+                .collect()
+        })
+        .collect()
+}
+
+/// Parse the kinds of piece to place, written as `piece:count` pairs separated by semicolons
+fn parse_mixture(mixture: &str) -> Vec<(Piece, usize)> {
+    mixture
+        .split(';')
+        .filter(|m| !m.trim().is_empty())
+        .map(|m| {
+            let mut parts = m.rsplitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(count), Some(piece)) => {
+                    let piece = piece.trim();
+                    let piece = Piece::from_name(piece)
+                        .or_else(|_| Piece::from_betza(piece))
+                        .unwrap_or_else(|e| panic!("Invalid piece provided! {}", e));
+                    let count = count.trim().parse().expect("Invalid piece count!");
+                    (piece, count)
+                }
+                _ => panic!("Invalid mixture provided!"),
+            }
+        })
+        .collect()
 }
@@ -0,0 +1,332 @@
+use crate::betza::parse_betza;
+use crate::bitboard::BitBoard;
+use crate::symmetry::Symmetry;
+use crate::topology::Topology;
+use std::fmt;
+
+/// Jumps of a knight, relative to its cell
+pub const KNIGHT_JUMPS: [(i64, i64); 8] = [
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+];
+
+/// A single direction of attack of a piece
+///
+/// The piece attacks the cells `(dx, dy)`, `(2dx, 2dy)`, ... up to `range` steps away, or
+/// until the edge of the board when there is no range. Leapers have a range of 1 and jump
+/// over obstacles, riders stop at the first obstacle on their way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Movement {
+    dx: i64,
+    dy: i64,
+    range: Option<usize>,
+}
+
+impl Movement {
+    pub fn new(dx: i64, dy: i64, range: Option<usize>) -> Self {
+        Movement { dx, dy, range }
+    }
+
+    /// The movement in the 8 directions obtained by swapping and negating the offsets
+    pub fn symmetric(dx: i64, dy: i64, range: Option<usize>) -> Vec<Movement> {
+        let mut movements = vec![];
+        for (a, b) in [(dx, dy), (dy, dx)].iter() {
+            for (sa, sb) in [(1, 1), (1, -1), (-1, 1), (-1, -1)].iter() {
+                let movement = Movement::new(a * sa, b * sb, range);
+                if !movements.contains(&movement) {
+                    movements.push(movement);
+                }
+            }
+        }
+        movements
+    }
+
+    pub fn get_offset(&self) -> (i64, i64) {
+        (self.dx, self.dy)
+    }
+
+    pub fn get_range(&self) -> &Option<usize> {
+        &self.range
+    }
+}
+
+/// Attack pattern of a kind of piece
+///
+/// Attacks are always symmetric: a piece attacks another one exactly when it is attacked
+/// back, so the movements of a piece are closed under negation.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::bitboard::BitBoard;
+/// use andaluz_core::piece::Piece;
+///
+/// let knight = Piece::knight();
+/// let mask = knight.attack_mask(8, 8, 0, &BitBoard::new(64));
+///
+/// assert_eq!(mask.iter_ones().collect::<Vec<usize>>(), vec![10, 17]);
+/// assert_eq!(Piece::from_name("amazon").unwrap().get_movements().len(), 16);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Piece {
+    name: String,
+    movements: Vec<Movement>,
+}
+
+impl Piece {
+    /// Build a piece from its movements, adding the reverse of each one
+    pub fn new(name: &str, movements: &[Movement]) -> Self {
+        let mut all = vec![];
+        for m in movements {
+            all.push(*m);
+            all.push(Movement::new(-m.dx, -m.dy, m.range));
+        }
+        all.retain(|m| m.dx != 0 || m.dy != 0);
+        all.sort();
+        all.dedup();
+
+        Piece {
+            name: name.to_string(),
+            movements: all,
+        }
+    }
+
+    /// Piece moving once in every direction of `(dx, dy)`
+    pub fn leaper(name: &str, dx: i64, dy: i64) -> Self {
+        Piece::new(name, &Movement::symmetric(dx, dy, Some(1)))
+    }
+
+    /// Piece repeating `(dx, dy)` in every direction until the edge of the board
+    pub fn rider(name: &str, dx: i64, dy: i64) -> Self {
+        Piece::new(name, &Movement::symmetric(dx, dy, None))
+    }
+
+    /// Piece with the movements of all the `pieces`
+    pub fn compound(name: &str, pieces: &[Piece]) -> Self {
+        let movements = pieces
+            .iter()
+            .flat_map(|p| p.movements.iter().cloned())
+            .collect::<Vec<Movement>>();
+        Piece::new(name, &movements)
+    }
+
+    pub fn queen() -> Self {
+        Piece::compound("Queen", &[Piece::rook(), Piece::bishop()])
+    }
+
+    pub fn rook() -> Self {
+        Piece::rider("Rook", 1, 0)
+    }
+
+    pub fn bishop() -> Self {
+        Piece::rider("Bishop", 1, 1)
+    }
+
+    pub fn king() -> Self {
+        Piece::compound(
+            "King",
+            &[Piece::leaper("Wazir", 1, 0), Piece::leaper("Ferz", 1, 1)],
+        )
+    }
+
+    pub fn knight() -> Self {
+        let jumps = KNIGHT_JUMPS
+            .iter()
+            .map(|(dx, dy)| Movement::new(*dx, *dy, Some(1)))
+            .collect::<Vec<Movement>>();
+        Piece::new("Knight", &jumps)
+    }
+
+    /// Queen and knight, under its fairy chess name
+    pub fn amazon() -> Self {
+        Piece::compound("Amazon", &[Piece::queen(), Piece::knight()])
+    }
+
+    /// Alias of `amazon`, the piece of the n-superqueens problem
+    pub fn superqueen() -> Self {
+        Piece::amazon()
+    }
+
+    /// Queen of a hexagonal board in axial coordinates, riding along the three hex axes
+    pub fn hex_queen() -> Self {
+        let axes = [(1, 0), (0, 1), (1, -1)]
+            .iter()
+            .map(|(dx, dy)| Movement::new(*dx, *dy, None))
+            .collect::<Vec<Movement>>();
+        Piece::new("Hex queen", &axes)
+    }
+
+    /// Built-in piece by its lowercase name
+    pub fn from_name(name: &str) -> Result<Piece, String> {
+        match name {
+            "queen" => Ok(Piece::queen()),
+            "rook" => Ok(Piece::rook()),
+            "bishop" => Ok(Piece::bishop()),
+            "king" => Ok(Piece::king()),
+            "knight" => Ok(Piece::knight()),
+            "superqueen" => Ok(Piece::superqueen()),
+            "amazon" => Ok(Piece::amazon()),
+            _ => Err(format!("Unknown piece '{}'", name)),
+        }
+    }
+
+    /// Piece described by its Betza movement notation, see `betza::parse_betza`
+    pub fn from_betza(notation: &str) -> Result<Piece, String> {
+        parse_betza(notation)
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_movements(&self) -> &Vec<Movement> {
+        &self.movements
+    }
+
+    /// Whether the transform maps the attacks of the piece onto themselves
+    pub fn is_invariant(&self, symmetry: &Symmetry) -> bool {
+        self.movements.iter().all(|m| {
+            let (dx, dy) = symmetry.apply_offset(m.dx, m.dy);
+            self.movements.contains(&Movement::new(dx, dy, m.range))
+        })
+    }
+
+    /// Whether the piece attacks exactly like a queen, so the line masks apply
+    pub fn is_queen(&self) -> bool {
+        self.movements.len() == 8
+            && self
+                .movements
+                .iter()
+                .all(|m| m.range.is_none() && m.dx.abs() <= 1 && m.dy.abs() <= 1)
+    }
+
+    /// Cells of a `cols` by `rows` board attacked from the cell `i`
+    ///
+    /// Riders stop at the `obstacles`, and the obstacles themselves are never attacked.
+    pub fn attack_mask(
+        &self,
+        cols: usize,
+        rows: usize,
+        i: usize,
+        obstacles: &BitBoard,
+    ) -> BitBoard {
+        self.attack_mask_on(&Topology::Plane, cols, rows, i, obstacles)
+    }
+
+    /// Cells attacked from the cell `i` when the edges of the board are glued by `topology`
+    ///
+    /// Riders going around a glued board stop once they get back to their own cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::bitboard::BitBoard;
+    /// use andaluz_core::piece::Piece;
+    /// use andaluz_core::topology::Topology;
+    ///
+    /// let mask = Piece::queen().attack_mask_on(&Topology::Torus, 5, 5, 0, &BitBoard::new(25));
+    ///
+    /// assert_eq!(mask.count_ones(), 16);
+    /// assert!(mask.get(24));
+    /// ```
+    pub fn attack_mask_on(
+        &self,
+        topology: &Topology,
+        cols: usize,
+        rows: usize,
+        i: usize,
+        obstacles: &BitBoard,
+    ) -> BitBoard {
+        let mut mask = BitBoard::new(cols * rows);
+        let (x, y) = ((i % cols) as i64, (i / cols) as i64);
+
+        // Lines of a glued board close after visiting each cell once in each direction
+        let max_steps = 2 * cols * rows;
+
+        for m in &self.movements {
+            let range = m.range.unwrap_or(max_steps).min(max_steps);
+            let (mut px, mut py, mut dx, mut dy) = (x, y, m.dx, m.dy);
+
+            for _ in 0..range {
+                match topology.step(cols, rows, px, py, dx, dy) {
+                    Some(next) => {
+                        px = next.0;
+                        py = next.1;
+                        dx = next.2;
+                        dy = next.3;
+                    }
+                    None => break,
+                }
+
+                let p = (px + cols as i64 * py) as usize;
+                if p == i || obstacles.get(p) {
+                    break;
+                }
+                mask.set(p);
+            }
+        }
+
+        mask
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attacks(piece: &Piece, cols: usize, i: usize) -> usize {
+        piece
+            .attack_mask(cols, cols, i, &BitBoard::new(cols * cols))
+            .count_ones()
+    }
+
+    #[test]
+    fn built_in_attacks() {
+        // Center of a 5x5 board
+        assert_eq!(attacks(&Piece::rook(), 5, 12), 8);
+        assert_eq!(attacks(&Piece::bishop(), 5, 12), 8);
+        assert_eq!(attacks(&Piece::queen(), 5, 12), 16);
+        assert_eq!(attacks(&Piece::king(), 5, 12), 8);
+        assert_eq!(attacks(&Piece::knight(), 5, 12), 8);
+        assert_eq!(attacks(&Piece::superqueen(), 5, 12), 24);
+
+        // Corner
+        assert_eq!(attacks(&Piece::king(), 5, 0), 3);
+        assert_eq!(attacks(&Piece::knight(), 5, 0), 2);
+
+        assert!(Piece::queen().is_queen());
+        assert!(!Piece::amazon().is_queen());
+        assert_eq!(Piece::superqueen(), Piece::amazon());
+        assert!(Piece::from_name("pawn").is_err());
+    }
+
+    #[test]
+    fn obstacles_stop_riders() {
+        let mut obstacles = BitBoard::new(25);
+        obstacles.set(13);
+
+        // The rook at (1, 3) stops before (4, 3), the knight jumps over it
+        assert_eq!(
+            Piece::rook().attack_mask(5, 5, 10, &obstacles).count_ones(),
+            6
+        );
+        assert_eq!(
+            Piece::knight()
+                .attack_mask(5, 5, 12, &obstacles)
+                .count_ones(),
+            8
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::bitboard::BitBoard;
+use std::fmt;
+
+/// Square board split into colored regions, for the "Queens" and Star Battle puzzles
+///
+/// Every row, column and region must hold the same number of stars, one for the "Queens"
+/// puzzle, and no two stars may touch, not even diagonally. Regions are read from a grid of
+/// letters, one letter per region, with the rows separated by new lines or semicolons and
+/// the first row of the grid on top, as in `to_multiline_string`.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::region::RegionBoard;
+/// use andaluz_core::solver::Solver;
+///
+/// let mut board = RegionBoard::from_letters("BAADD;BCCCD;CCCCD;CCCED;CCEEE").unwrap();
+///
+/// let mut solver = Solver::new();
+/// assert!(solver.solve_regions(&mut board).unwrap().is_solved());
+/// assert_eq!(board.get_stars(), vec![(4, 1), (2, 2), (5, 3), (1, 4), (3, 5)]);
+///
+/// assert!(solver.has_unique_solution(&board).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegionBoard {
+    side: usize,
+    letters: Vec<char>,
+    regions: Vec<usize>,
+    stars_per_region: usize,
+    stars: BitBoard,
+}
+
+impl RegionBoard {
+    /// Parse the regions from a square grid of letters
+    pub fn from_letters(grid: &str) -> Result<Self, String> {
+        let lines = grid
+            .split(['\n', ';'])
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<&str>>();
+        let side = lines.len();
+
+        let mut letters = vec![];
+        let mut regions = vec![0; side * side];
+        for (row, line) in lines.iter().enumerate() {
+            let cells = line.chars().collect::<Vec<char>>();
+            if cells.len() != side {
+                return Err(format!(
+                    "Row '{}' has {} cells instead of {}",
+                    line,
+                    cells.len(),
+                    side
+                ));
+            }
+
+            let y = side - row;
+            for (x, c) in cells.into_iter().enumerate() {
+                let region = match letters.iter().position(|l| *l == c) {
+                    Some(r) => r,
+                    None => {
+                        letters.push(c);
+                        letters.len() - 1
+                    }
+                };
+                regions[x + side * (y - 1)] = region;
+            }
+        }
+
+        if letters.len() != side {
+            return Err(format!(
+                "A board of side {} needs {} regions, found {}",
+                side,
+                side,
+                letters.len()
+            ));
+        }
+
+        Ok(RegionBoard {
+            side,
+            letters,
+            regions,
+            stars_per_region: 1,
+            stars: BitBoard::new(side * side),
+        })
+    }
+
+    pub fn get_side(&self) -> &usize {
+        &self.side
+    }
+
+    /// Letters of the regions, in order of first appearance in the grid
+    pub fn get_letters(&self) -> &Vec<char> {
+        &self.letters
+    }
+
+    /// Letter of the region of the cell `(x, y)`
+    pub fn get_region(&self, x: &usize, y: &usize) -> Result<&char, String> {
+        let i = self.get_i(x, y)?;
+        Ok(&self.letters[self.regions[i]])
+    }
+
+    pub fn get_stars_per_region(&self) -> &usize {
+        &self.stars_per_region
+    }
+
+    /// Set the number of stars of every row, column and region, removing the placed stars
+    pub fn set_stars_per_region(&mut self, stars: usize) -> Result<(), String> {
+        if stars == 0 || 2 * stars > self.side + 1 {
+            return Err(format!(
+                "Unable to fit {} non touching stars in a row of {} cells",
+                stars, self.side
+            ));
+        }
+
+        self.stars_per_region = stars;
+        self.stars.clear();
+        Ok(())
+    }
+
+    fn get_i(&self, x: &usize, y: &usize) -> Result<usize, String> {
+        if *x < 1 || *y < 1 || *x > self.side || *y > self.side {
+            return Err(format!("Coordinates ({}, {}) out of bounds", x, y));
+        }
+        Ok(x - 1 + self.side * (y - 1))
+    }
+
+    pub fn is_star(&self, x: &usize, y: &usize) -> bool {
+        self.get_i(x, y).is_ok_and(|i| self.stars.get(i))
+    }
+
+    /// Whether a star fits on the cell `(x, y)` with the stars already placed
+    ///
+    /// The row, column and region of the cell must have room left, and none of the eight
+    /// neighbours may hold a star.
+    pub fn allows(&self, x: &usize, y: &usize) -> bool {
+        let i = match self.get_i(x, y) {
+            Ok(i) => i,
+            Err(_) => return false,
+        };
+        if self.stars.get(i) {
+            return false;
+        }
+
+        let (mut row, mut col, mut region) = (0, 0, 0);
+        for s in self.stars.iter_ones() {
+            let (sx, sy) = (s % self.side + 1, s / self.side + 1);
+            if sx.abs_diff(*x) <= 1 && sy.abs_diff(*y) <= 1 {
+                return false;
+            }
+            row += (sy == *y) as usize;
+            col += (sx == *x) as usize;
+            region += (self.regions[s] == self.regions[i]) as usize;
+        }
+
+        row < self.stars_per_region && col < self.stars_per_region && region < self.stars_per_region
+    }
+
+    /// Put or remove a star from a cell
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::region::RegionBoard;
+    ///
+    /// let mut board = RegionBoard::from_letters("AAB;ACB;CCB").unwrap();
+    ///
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// assert!(board.toggle_cell(&2, &2).is_err());
+    /// assert!(board.toggle_cell(&3, &1).is_err());
+    /// assert!(board.toggle_cell(&3, &3).is_ok());
+    /// assert_eq!(board.get_stars(), vec![(1, 1), (3, 3)]);
+    /// ```
+    pub fn toggle_cell(&mut self, x: &usize, y: &usize) -> Result<(), String> {
+        let i = self.get_i(x, y)?;
+
+        if !self.stars.get(i) && !self.allows(x, y) {
+            return Err(format!("Unable to place a star on ({}, {})", x, y));
+        }
+
+        self.stars.toggle(i);
+        Ok(())
+    }
+
+    /// Remove every star
+    pub fn reset(&mut self) {
+        self.stars.clear();
+    }
+
+    /// Coordinates of the stars, row by row from the bottom
+    pub fn get_stars(&self) -> Vec<(usize, usize)> {
+        self.stars
+            .iter_ones()
+            .map(|i| (i % self.side + 1, i / self.side + 1))
+            .collect()
+    }
+
+    /// Number of stars of a complete solution
+    pub fn get_goal(&self) -> usize {
+        self.side * self.stars_per_region
+    }
+
+    /// Whether every row, column and region holds its stars
+    ///
+    /// Stars can only be placed where they fit, so the count of stars is enough.
+    pub fn is_solved(&self) -> bool {
+        self.stars.count_ones() == self.get_goal()
+    }
+
+    /// Star bits of the cells, packed MSB-first row by row from the bottom
+    pub fn get_signature(&self) -> Vec<u8> {
+        self.stars.to_signature()
+    }
+
+    /// Grid of the region letters with the stars as `*`, the top row first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::region::RegionBoard;
+    ///
+    /// let mut board = RegionBoard::from_letters("AAB;ACB;CCB").unwrap();
+    /// board.toggle_cell(&1, &1).unwrap();
+    ///
+    /// assert_eq!(board.to_multiline_string(), "AAB\nACB\n*CB\n");
+    /// ```
+    pub fn to_multiline_string(&self) -> String {
+        (1..=self.side)
+            .rev()
+            .map(|y| {
+                let row = (1..=self.side)
+                    .map(|x| {
+                        let i = x - 1 + self.side * (y - 1);
+                        if self.stars.get(i) {
+                            '*'
+                        } else {
+                            self.letters[self.regions[i]]
+                        }
+                    })
+                    .collect::<String>();
+                format!("{}\n", row)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for RegionBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let board = (0..self.side * self.side)
+            .map(|i| if self.stars.get(i) { '1' } else { '0' })
+            .collect::<String>();
+        write!(f, "{}", board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_regions() {
+        let board = RegionBoard::from_letters("AB\nBA\n").unwrap();
+        assert_eq!(board.get_region(&1, &2).unwrap(), &'A');
+        assert_eq!(board.get_region(&1, &1).unwrap(), &'B');
+        assert_eq!(board.get_letters(), &vec!['A', 'B']);
+
+        assert!(RegionBoard::from_letters("AB;B").is_err());
+        assert!(RegionBoard::from_letters("AB;CC").is_err());
+
+        let mut board = RegionBoard::from_letters("AABB;AABB;CCDD;CCDD").unwrap();
+        assert!(board.set_stars_per_region(2).is_ok());
+        assert!(board.set_stars_per_region(3).is_err());
+    }
+}
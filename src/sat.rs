@@ -0,0 +1,638 @@
+use crate::cnf::Cnf;
+
+/// Conflict-driven clause learning SAT solver
+///
+/// Clauses are watched by two literals, conflicts are analysed up to the first unique
+/// implication point and the learnt clause sends the search back to the level where it
+/// becomes unit. Decisions follow the VSIDS activity of the variables, each taking the last
+/// value it had, and the search restarts after a Luby sequence of conflicts, dropping the
+/// longer half of the learnt clauses when there are too many.
+///
+/// Literals are DIMACS literals, the variable `v` as `v` and its negation as `-v`.
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::sat::SatSolver;
+///
+/// let mut solver = SatSolver::new(3);
+/// solver.add_clause(&[1, 2]).unwrap();
+/// solver.add_clause(&[-1, 3]).unwrap();
+/// solver.add_clause(&[-2, 3]).unwrap();
+/// solver.add_clause(&[-3, -1]).unwrap();
+///
+/// assert_eq!(solver.solve().unwrap(), Some(vec![-1, 2, 3]));
+///
+/// solver.add_clause(&[-3]).unwrap();
+/// assert_eq!(solver.solve().unwrap(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SatSolver {
+    variables: usize,
+    clauses: Vec<Vec<usize>>,
+    learnt: Vec<bool>,
+    learnt_count: usize,
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<usize>,
+    trail_limits: Vec<usize>,
+    head: usize,
+    activity: Vec<f64>,
+    increment: f64,
+    phases: Vec<bool>,
+    heap: VariableHeap,
+    seen: Vec<bool>,
+    conflicts: u32,
+    max_conflicts: u32,
+    unsatisfiable: bool,
+}
+
+impl SatSolver {
+    pub fn new(variables: usize) -> Self {
+        let mut heap = VariableHeap::new(variables);
+        let activity = vec![0.0; variables];
+        (0..variables).for_each(|v| heap.push(v, &activity));
+
+        SatSolver {
+            variables,
+            clauses: vec![],
+            learnt: vec![],
+            learnt_count: 0,
+            watches: vec![vec![]; 2 * variables],
+            values: vec![None; variables],
+            levels: vec![0; variables],
+            reasons: vec![None; variables],
+            trail: vec![],
+            trail_limits: vec![],
+            head: 0,
+            activity,
+            increment: 1.0,
+            phases: vec![false; variables],
+            heap,
+            seen: vec![false; variables],
+            conflicts: 0,
+            max_conflicts: u32::MAX,
+            unsatisfiable: false,
+        }
+    }
+
+    /// Solver holding every clause of a formula
+    pub fn from_cnf(cnf: &Cnf) -> Result<Self, String> {
+        let mut solver = SatSolver::new(*cnf.get_variables());
+        for clause in cnf.get_clauses() {
+            solver.add_clause(clause)?;
+        }
+        Ok(solver)
+    }
+
+    pub fn get_variables(&self) -> &usize {
+        &self.variables
+    }
+
+    /// Conflicts met by the searches so far
+    pub fn get_conflicts(&self) -> &u32 {
+        &self.conflicts
+    }
+
+    /// Give up a search once the solver has met this many conflicts
+    pub fn set_max_conflicts(&mut self, max_conflicts: u32) {
+        self.max_conflicts = max_conflicts;
+    }
+
+    /// Add a clause, dropping it when always satisfied
+    pub fn add_clause(&mut self, clause: &[i32]) -> Result<(), String> {
+        self.backtrack(0);
+
+        let mut literals = vec![];
+        for literal in clause {
+            if *literal == 0 || literal.unsigned_abs() as usize > self.variables {
+                return Err(format!("Invalid literal {}", literal));
+            }
+            literals.push(to_literal(*literal));
+        }
+        literals.sort_unstable();
+        literals.dedup();
+
+        if literals.windows(2).any(|w| w[0] ^ 1 == w[1])
+            || literals.iter().any(|l| self.value(*l) == Some(true))
+        {
+            return Ok(());
+        }
+        literals.retain(|l| self.value(*l).is_none());
+
+        match literals.len() {
+            0 => self.unsatisfiable = true,
+            1 => self.enqueue(literals[0], None),
+            _ => {
+                self.attach(literals, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// Values of all the variables satisfying every clause, `None` when there are none
+    ///
+    /// The search is cut short with an error after the maximum number of conflicts.
+    pub fn solve(&mut self) -> Result<Option<Vec<i32>>, String> {
+        self.backtrack(0);
+        if self.unsatisfiable {
+            return Ok(None);
+        }
+
+        let mut restarts = 0;
+        let mut restart_limit = 100 * luby(restarts);
+        let mut restart_conflicts = 0;
+        let mut max_learnt = (self.clauses.len() / 3).max(1000) as f64;
+
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.conflicts += 1;
+                restart_conflicts += 1;
+
+                if self.trail_limits.is_empty() {
+                    self.unsatisfiable = true;
+                    return Ok(None);
+                }
+
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let reason = self.attach(learnt, true);
+                    self.enqueue(asserting, Some(reason));
+                }
+                self.increment /= 0.95;
+
+                if self.conflicts >= self.max_conflicts {
+                    self.backtrack(0);
+                    return Err(format!(
+                        "Search cut short after {} conflicts",
+                        self.conflicts
+                    ));
+                }
+                continue;
+            }
+
+            if restart_conflicts >= restart_limit {
+                self.backtrack(0);
+                restarts += 1;
+                restart_limit = 100 * luby(restarts);
+                restart_conflicts = 0;
+                continue;
+            }
+
+            if self.trail_limits.is_empty() && self.learnt_count as f64 > max_learnt {
+                self.reduce();
+                max_learnt *= 1.1;
+            }
+
+            match self.pick_branch() {
+                None => {
+                    let model = self
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(v, value)| match value {
+                            Some(false) => -(v as i32 + 1),
+                            _ => v as i32 + 1,
+                        })
+                        .collect();
+                    return Ok(Some(model));
+                }
+                Some(literal) => {
+                    self.trail_limits.push(self.trail.len());
+                    self.enqueue(literal, None);
+                }
+            }
+        }
+    }
+
+    fn value(&self, literal: usize) -> Option<bool> {
+        literal_value(&self.values, literal)
+    }
+
+    fn enqueue(&mut self, literal: usize, reason: Option<usize>) {
+        let v = literal >> 1;
+        self.values[v] = Some(literal & 1 == 0);
+        self.levels[v] = self.trail_limits.len();
+        self.reasons[v] = reason;
+        self.trail.push(literal);
+    }
+
+    /// Store a clause of at least two literals, watching the first two
+    fn attach(&mut self, literals: Vec<usize>, learnt: bool) -> usize {
+        let index = self.clauses.len();
+        self.watches[literals[0]].push(index);
+        self.watches[literals[1]].push(index);
+        self.clauses.push(literals);
+        self.learnt.push(learnt);
+        self.learnt_count += learnt as usize;
+        index
+    }
+
+    /// Set the literals implied by the trail, returning the clause left false if any
+    fn propagate(&mut self) -> Option<usize> {
+        while self.head < self.trail.len() {
+            let falsified = self.trail[self.head] ^ 1;
+            self.head += 1;
+
+            let mut watching = std::mem::take(&mut self.watches[falsified]);
+            let mut kept = 0;
+            let mut conflict = None;
+
+            for k in 0..watching.len() {
+                let index = watching[k];
+                if conflict.is_some() {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+
+                // The falsified literal goes second, the other watch first
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                if literal_value(&self.values, clause[0]) == Some(true) {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+
+                let values = &self.values;
+                let replacement =
+                    (2..clause.len()).find(|j| literal_value(values, clause[*j]) != Some(false));
+                if let Some(j) = replacement {
+                    clause.swap(1, j);
+                    self.watches[clause[1]].push(index);
+                    continue;
+                }
+
+                watching[kept] = index;
+                kept += 1;
+                let unit = clause[0];
+                if literal_value(&self.values, unit) == Some(false) {
+                    conflict = Some(index);
+                } else {
+                    self.enqueue(unit, Some(index));
+                }
+            }
+
+            watching.truncate(kept);
+            self.watches[falsified] = watching;
+
+            if conflict.is_some() {
+                self.head = self.trail.len();
+                return conflict;
+            }
+        }
+
+        None
+    }
+
+    /// Learnt clause of a conflict, its asserting literal first and a literal of the level
+    /// to go back to second, with that level
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let level = self.trail_limits.len();
+        let mut learnt = vec![0];
+        let mut pending = 0;
+        let mut index = self.trail.len();
+        let mut clause = conflict;
+        let mut skip = 0;
+
+        loop {
+            for j in skip..self.clauses[clause].len() {
+                let literal = self.clauses[clause][j];
+                let v = literal >> 1;
+                if self.seen[v] || self.levels[v] == 0 {
+                    continue;
+                }
+
+                self.seen[v] = true;
+                self.bump(v);
+                if self.levels[v] == level {
+                    pending += 1;
+                } else {
+                    learnt.push(literal);
+                }
+            }
+
+            // Latest literal of the trail taking part in the conflict
+            loop {
+                index -= 1;
+                if self.seen[self.trail[index] >> 1] {
+                    break;
+                }
+            }
+            let literal = self.trail[index];
+            self.seen[literal >> 1] = false;
+            pending -= 1;
+
+            if pending == 0 {
+                learnt[0] = literal ^ 1;
+                break;
+            }
+            clause = self.reasons[literal >> 1].expect("Implied literal without reason");
+            skip = 1;
+        }
+
+        // Drop the literals implied by the other literals of the clause
+        let marked = learnt[1..].to_vec();
+        let asserting = learnt[0];
+        learnt.retain(|literal| match self.reasons[literal >> 1] {
+            Some(reason) if *literal != asserting => self.clauses[reason][1..]
+                .iter()
+                .any(|l| !self.seen[l >> 1] && self.levels[l >> 1] > 0),
+            _ => true,
+        });
+        marked.iter().for_each(|l| self.seen[l >> 1] = false);
+
+        let mut back = 0;
+        for j in 1..learnt.len() {
+            if self.levels[learnt[j] >> 1] > back {
+                back = self.levels[learnt[j] >> 1];
+                learnt.swap(1, j);
+            }
+        }
+
+        (learnt, back)
+    }
+
+    fn bump(&mut self, v: usize) {
+        self.activity[v] += self.increment;
+        if self.activity[v] > 1e100 {
+            self.activity.iter_mut().for_each(|a| *a *= 1e-100);
+            self.increment *= 1e-100;
+        }
+        self.heap.increase(v, &self.activity);
+    }
+
+    /// Undo the assignments past the decision level `level`
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+
+        let start = self.trail_limits[level];
+        for literal in self.trail.drain(start..).rev() {
+            let v = literal >> 1;
+            self.phases[v] = self.values[v] == Some(true);
+            self.values[v] = None;
+            self.reasons[v] = None;
+            if !self.heap.contains(v) {
+                self.heap.push(v, &self.activity);
+            }
+        }
+        self.trail_limits.truncate(level);
+        self.head = self.trail.len();
+    }
+
+    /// Most active unassigned variable, with its last value
+    fn pick_branch(&mut self) -> Option<usize> {
+        while let Some(v) = self.heap.pop(&self.activity) {
+            if self.values[v].is_none() {
+                return Some(2 * v + !self.phases[v] as usize);
+            }
+        }
+        None
+    }
+
+    /// Drop the satisfied clauses, the false literals and the longer half of the learnt
+    /// clauses, only at the top level where no clause is the reason of a literal
+    fn reduce(&mut self) {
+        let mut lengths = self
+            .clauses
+            .iter()
+            .zip(&self.learnt)
+            .filter(|(_, learnt)| **learnt)
+            .map(|(c, _)| c.len())
+            .collect::<Vec<usize>>();
+        lengths.sort_unstable();
+        let limit = lengths.get(lengths.len() / 2).cloned().unwrap_or(0);
+
+        let clauses = std::mem::take(&mut self.clauses);
+        let learnt = std::mem::take(&mut self.learnt);
+        self.learnt_count = 0;
+        self.watches.iter_mut().for_each(|w| w.clear());
+        self.reasons.iter_mut().for_each(|r| *r = None);
+
+        for (mut clause, learnt) in clauses.into_iter().zip(learnt) {
+            if (learnt && clause.len() > limit.max(2))
+                || clause.iter().any(|l| self.value(*l) == Some(true))
+            {
+                continue;
+            }
+            clause.retain(|l| self.value(*l).is_none());
+            self.attach(clause, learnt);
+        }
+    }
+}
+
+fn to_literal(literal: i32) -> usize {
+    2 * (literal.unsigned_abs() as usize - 1) + (literal < 0) as usize
+}
+
+fn literal_value(values: &[Option<bool>], literal: usize) -> Option<bool> {
+    values[literal >> 1].map(|v| v == (literal & 1 == 0))
+}
+
+/// Element `i` of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ...
+fn luby(i: u32) -> u32 {
+    let mut size = 1;
+    let mut power = 0;
+    while size < i + 1 {
+        size = 2 * size + 1;
+        power += 1;
+    }
+
+    let mut i = i;
+    while size - 1 != i {
+        size = (size - 1) / 2;
+        power -= 1;
+        i %= size;
+    }
+    1 << power
+}
+
+/// Binary heap of variables, the most active on top
+#[derive(Debug, Clone)]
+struct VariableHeap {
+    heap: Vec<usize>,
+    positions: Vec<Option<usize>>,
+}
+
+impl VariableHeap {
+    fn new(variables: usize) -> Self {
+        VariableHeap {
+            heap: vec![],
+            positions: vec![None; variables],
+        }
+    }
+
+    fn contains(&self, v: usize) -> bool {
+        self.positions[v].is_some()
+    }
+
+    fn push(&mut self, v: usize, activity: &[f64]) {
+        self.positions[v] = Some(self.heap.len());
+        self.heap.push(v);
+        self.sift_up(self.heap.len() - 1, activity);
+    }
+
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let top = self.heap.swap_remove(0);
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.positions[self.heap[0]] = Some(0);
+            self.sift_down(0, activity);
+        }
+        Some(top)
+    }
+
+    /// Move up a variable whose activity grew
+    fn increase(&mut self, v: usize, activity: &[f64]) {
+        if let Some(position) = self.positions[v] {
+            self.sift_up(position, activity);
+        }
+    }
+
+    fn sift_up(&mut self, mut position: usize, activity: &[f64]) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if activity[self.heap[parent]] >= activity[self.heap[position]] {
+                break;
+            }
+            self.swap(parent, position);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize, activity: &[f64]) {
+        loop {
+            let mut largest = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child]] > activity[self.heap[largest]]
+                {
+                    largest = child;
+                }
+            }
+            if largest == position {
+                break;
+            }
+            self.swap(largest, position);
+            position = largest;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn random_formulas_match_brute_force() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..300 {
+            let variables = 12;
+            let clauses = (0..40 + rng.below(30))
+                .map(|_| {
+                    (0..3)
+                        .map(|_| {
+                            let v = rng.below(variables) as i32 + 1;
+                            if rng.below(2) == 0 {
+                                v
+                            } else {
+                                -v
+                            }
+                        })
+                        .collect::<Vec<i32>>()
+                })
+                .collect::<Vec<Vec<i32>>>();
+
+            let satisfies = |assignment: &dyn Fn(i32) -> bool| {
+                clauses
+                    .iter()
+                    .all(|c| c.iter().any(|l| assignment(l.abs()) == (*l > 0)))
+            };
+            let expected =
+                (0..1_u32 << variables).any(|bits| satisfies(&|v| bits & (1 << (v - 1)) != 0));
+
+            let mut solver = SatSolver::new(variables);
+            clauses.iter().for_each(|c| solver.add_clause(c).unwrap());
+
+            match solver.solve().unwrap() {
+                Some(model) => {
+                    assert!(expected);
+                    assert!(satisfies(&|v| model[v as usize - 1] > 0));
+                }
+                None => assert!(!expected),
+            }
+        }
+    }
+
+    #[test]
+    fn pigeonhole_is_unsatisfiable() {
+        // Seven pigeons in six holes, the variable 6p + h + 1 puts the pigeon p in the hole h
+        let (pigeons, holes) = (7, 6);
+        let mut solver = SatSolver::new(pigeons * holes);
+        let var = |p: usize, h: usize| (holes * p + h + 1) as i32;
+
+        for p in 0..pigeons {
+            solver
+                .add_clause(&(0..holes).map(|h| var(p, h)).collect::<Vec<i32>>())
+                .unwrap();
+        }
+        for h in 0..holes {
+            for p in 0..pigeons {
+                for q in p + 1..pigeons {
+                    solver.add_clause(&[-var(p, h), -var(q, h)]).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(solver.solve().unwrap(), None);
+        assert!(solver.get_conflicts() > &0);
+    }
+
+    #[test]
+    fn conflict_budget() {
+        // Both values of the first variable lead to a conflict
+        let mut solver = SatSolver::new(3);
+        for clause in &[[1, 2], [1, -2], [-1, 3], [-1, -3]] {
+            solver.add_clause(clause).unwrap();
+        }
+        solver.set_max_conflicts(0);
+        assert!(solver.solve().is_err());
+    }
+
+    #[test]
+    fn invalid_literals() {
+        let mut solver = SatSolver::new(2);
+        assert!(solver.add_clause(&[3]).is_err());
+        assert!(solver.add_clause(&[0]).is_err());
+        assert!(solver.add_clause(&[-3]).is_err());
+    }
+
+    #[test]
+    fn luby_sequence() {
+        let expected = [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8];
+        assert_eq!((0..15).map(luby).collect::<Vec<_>>(), expected);
+    }
+}
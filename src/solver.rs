@@ -1,7 +1,17 @@
+use crate::bitboard::BitBoard;
 use crate::board::Board;
+use crate::board_nd::BoardND;
+use crate::cell::{Cell, Color};
 use crate::classifier::Classifier;
+use crate::cnf::{self, Encoding};
+use crate::collinear::CollinearCells;
+use crate::coloring::Coloring;
+use crate::constraint::Constraint;
+use crate::goal::Goal;
 use crate::heuristic_implementation::bruteforce::BruteForce;
 use crate::heuristic_implementation::HeuristicImplementation;
+use crate::region::RegionBoard;
+use crate::sat::SatSolver;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
@@ -9,6 +19,8 @@ use std::collections::HashSet;
 pub struct SolutionNode {
     pub x: usize,
     pub y: usize,
+    /// Kind of piece of the mixture of the board, `0` when not mixing pieces
+    pub kind: usize,
     pub score: f64,
 }
 
@@ -26,7 +38,11 @@ impl PartialEq for SolutionNode {
 
 impl SolutionNode {
     pub fn new(x: usize, y: usize, score: f64) -> Self {
-        SolutionNode { x, y, score }
+        SolutionNode::with_kind(x, y, 0, score)
+    }
+
+    pub fn with_kind(x: usize, y: usize, kind: usize, score: f64) -> Self {
+        SolutionNode { x, y, kind, score }
     }
 }
 
@@ -36,15 +52,25 @@ pub struct SolverResult {
     heuristics_description: String,
     jumps: u32,
     solution: Option<Vec<u8>>,
+    best_progress: usize,
+    best: Vec<u8>,
+    optimal: bool,
+    value: Option<i64>,
+    unsatisfiable: bool,
 }
 
 impl SolverResult {
     pub fn new(board: Vec<u8>, heuristics_description: String) -> Self {
         SolverResult {
+            best: board.clone(),
             board,
             jumps: 0,
             heuristics_description,
             solution: None,
+            best_progress: 0,
+            optimal: false,
+            value: None,
+            unsatisfiable: false,
         }
     }
 
@@ -52,6 +78,10 @@ impl SolverResult {
         self.jumps += 1;
     }
 
+    pub fn set_jumps(&mut self, jumps: u32) {
+        self.jumps = jumps;
+    }
+
     pub fn get_jumps(&self) -> &u32 {
         &self.jumps
     }
@@ -75,11 +105,58 @@ impl SolverResult {
     pub fn get_heuristics_description(&self) -> &String {
         &self.heuristics_description
     }
+
+    /// Keep the board if it went further towards the goal than the best one so far
+    pub fn update_best(&mut self, progress: usize, signature: &[u8]) {
+        if progress > self.best_progress {
+            self.best_progress = progress;
+            self.best = signature.to_vec();
+        }
+    }
+
+    /// Progress towards the goal of the best board found, the number of pieces by default
+    pub fn get_best_progress(&self) -> &usize {
+        &self.best_progress
+    }
+
+    /// Signature of the best board found
+    pub fn get_best(&self) -> &Vec<u8> {
+        &self.best
+    }
+
+    pub fn set_optimal(&mut self, optimal: bool) {
+        self.optimal = optimal;
+    }
+
+    /// Whether the search proved the solution optimal, by not running out of jumps
+    pub fn is_optimal(&self) -> bool {
+        self.optimal
+    }
+
+    pub fn set_value(&mut self, value: i64) {
+        self.value = Some(value);
+    }
+
+    /// Value of the solution for the modes optimizing one, such as its total weight
+    pub fn get_value(&self) -> &Option<i64> {
+        &self.value
+    }
+
+    pub fn set_unsatisfiable(&mut self, unsatisfiable: bool) {
+        self.unsatisfiable = unsatisfiable;
+    }
+
+    /// Whether the search proved that the board has no solution
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.unsatisfiable
+    }
 }
 
 #[derive(Debug)]
 pub struct Solver<'a> {
     classifier: Classifier<'a>,
+    goal: Option<Box<dyn Goal + 'a>>,
+    constraints: Vec<Box<dyn Constraint + 'a>>,
     depleted_signatures: HashSet<Vec<u8>>,
     solver_result: SolverResult,
     max_jumps: u32,
@@ -93,6 +170,8 @@ impl<'a> Solver<'a> {
 
         Solver {
             classifier,
+            goal: None,
+            constraints: vec![],
             depleted_signatures,
             solver_result,
             max_jumps: 100000,
@@ -105,6 +184,8 @@ impl<'a> Solver<'a> {
             board.get_signature().clone(),
             format!("{}", self.classifier),
         );
+        self.solver_result
+            .update_best(self.get_progress(board), board.get_signature());
     }
 
     pub fn set_max_jumps(&mut self, max_jumps: u32) {
@@ -115,13 +196,1080 @@ impl<'a> Solver<'a> {
         self.classifier.push_heuristic(hi);
     }
 
+    /// Look for boards reaching `goal` instead of `Board::is_solved`
+    ///
+    /// When the goal isn't reached, `solve` leaves the board with the best placement found.
+    pub fn set_goal(&mut self, goal: impl Goal + 'a) {
+        self.goal = Some(Box::new(goal));
+    }
+
+    pub fn get_goal(&self) -> &Option<Box<dyn Goal + 'a>> {
+        &self.goal
+    }
+
+    /// Only place pieces where `constraint` allows them, along with the other constraints
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::constraint::NoLongDiagonals;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(6);
+    ///
+    /// let mut solver = Solver::new();
+    /// solver.push_constraint(NoLongDiagonals);
+    ///
+    /// assert!(solver.solve(&mut board).unwrap().is_solved());
+    /// assert!(board.get_queens().iter().all(|(x, y)| x != y && x + y != 7));
+    /// ```
+    pub fn push_constraint(&mut self, constraint: impl Constraint + 'a) {
+        self.constraints.push(Box::new(constraint));
+    }
+
+    pub fn get_constraints(&self) -> &Vec<Box<dyn Constraint + 'a>> {
+        &self.constraints
+    }
+
+    fn is_reached(&self, board: &Board) -> bool {
+        match &self.goal {
+            Some(goal) => goal.is_reached(board),
+            None => board.is_solved(),
+        }
+    }
+
+    fn get_progress(&self, board: &Board) -> usize {
+        match &self.goal {
+            Some(goal) => goal.progress(board),
+            None => board.get_occupancy().count_ones(),
+        }
+    }
+
     pub fn solve(&mut self, board: &mut Board) -> Result<&SolverResult, String> {
         if self.classifier.is_empty() {
             self.push_heuristic(BruteForce::new(1.0));
         }
 
         self.reset(board);
-        self.exec_solve(board)
+        self.exec_solve(board)?;
+
+        if self.goal.is_some() && !self.solver_result.is_solved() {
+            board.load_signature(self.solver_result.get_best())?;
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    /// Place as many pieces as possible, raising the goal of the board until it fails
+    ///
+    /// Every goal is searched with the whole jump budget, and the jumps of all the searches
+    /// are added up. The board is left with the best placement found. It is only optimal if
+    /// the search that failed was exhaustive, otherwise it is just a lower bound of the maximum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(6);
+    /// board.toggle_obstacle(&3, &2).unwrap();
+    ///
+    /// let mut solver = Solver::new();
+    /// assert!(solver.solve_maximum(&mut board).unwrap().is_solved());
+    /// assert_eq!(board.get_queens().len(), 7);
+    /// ```
+    pub fn solve_maximum(&mut self, board: &mut Board) -> Result<&SolverResult, String> {
+        let original = board.get_signature().clone();
+        let mut best: Option<Board> = None;
+        let mut jumps = 0_u32;
+        let mut optimal = true;
+
+        for goal in 1..=board.get_piece_bound() {
+            let mut attempt = board.clone();
+            attempt.set_goal(goal);
+
+            let solved = self.solve(&mut attempt)?.is_solved();
+            jumps = jumps.saturating_add(*self.solver_result.get_jumps());
+
+            if !solved {
+                // Running out of jumps doesn't prove the goal out of reach
+                optimal = self.solver_result.get_jumps() <= &self.max_jumps;
+                break;
+            }
+            best = Some(attempt);
+        }
+
+        self.solver_result = SolverResult::new(original, format!("{}", self.classifier));
+        self.solver_result.set_jumps(jumps);
+        self.solver_result.set_optimal(optimal);
+
+        if let Some(best) = best {
+            *board = best;
+            self.solver_result.set_solved(board.get_signature().clone());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    /// Place the goal number of queens on a board with any number of dimensions
+    ///
+    /// Queens are placed in cell order, so each placement is only tried once, and a branch is
+    /// dropped when the lines along the first dimension that still have an available cell
+    /// can't make up the missing queens. The heuristics don't apply to these boards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board_nd::BoardND;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = BoardND::new(4, 3).unwrap();
+    /// board.set_goal(7);
+    ///
+    /// let mut solver = Solver::new();
+    /// assert!(solver.solve_nd(&mut board).unwrap().is_solved());
+    /// assert_eq!(board.get_queens().len(), 7);
+    /// ```
+    pub fn solve_nd(&mut self, board: &mut BoardND) -> Result<&SolverResult, String> {
+        self.solver_result = SolverResult::new(
+            board.get_signature().clone(),
+            "Backtracking over the cells".to_string(),
+        );
+
+        if board.is_solved() {
+            self.solver_result.set_solved(board.get_signature().clone());
+            return Ok(&self.solver_result);
+        }
+
+        if self.exec_solve_nd(board, 0)? {
+            self.solver_result.set_solved(board.get_signature().clone());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_nd(&mut self, board: &mut BoardND, start: usize) -> Result<bool, String> {
+        if self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(false);
+        }
+
+        let cells = board
+            .get_available_cells()
+            .into_iter()
+            .filter(|c| c.get_xyi().2 >= &start)
+            .collect::<Vec<_>>();
+
+        // At most one queen fits on each line along the first dimension
+        let side = board.get_dimensions()[0];
+        let mut lines = cells
+            .iter()
+            .map(|c| c.get_xyi().2 / side)
+            .collect::<Vec<_>>();
+        lines.dedup();
+        if board.get_queens().len() + lines.len() < board.get_goal() {
+            return Ok(false);
+        }
+
+        for c in cells {
+            let i = *c.get_xyi().2;
+            let coords = board.get_coords(i);
+
+            board.toggle_cell(&coords)?;
+            self.solver_result.inc_jumps();
+
+            if board.is_solved() || self.exec_solve_nd(board, i + 1)? {
+                return Ok(true);
+            }
+
+            board.toggle_cell(&coords)?;
+        }
+
+        Ok(false)
+    }
+
+    /// Place two peaceable armies as large as possible, see `Board::set_armies`
+    ///
+    /// The size of the armies is raised until no placement is found. Every placement is
+    /// searched over the cells in order, each holding a white piece, a black piece or none,
+    /// and a branch is dropped when the cells left available can't hold the missing pieces.
+    /// Swapping the sides gives another placement, so the first piece is white. All the
+    /// searches share the `max_jumps` budget, and the board is left with the largest armies
+    /// found. They are only optimal if the search that failed was exhaustive, otherwise their
+    /// size is just a lower bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cell::Color;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(5);
+    ///
+    /// let mut solver = Solver::new();
+    /// let result = solver.solve_peaceable(&mut board).unwrap();
+    ///
+    /// assert!(result.is_solved() && result.is_optimal());
+    /// assert_eq!(result.get_best_progress(), &4);
+    /// assert_eq!(board.get_army(&Color::Black).len(), 4);
+    /// ```
+    pub fn solve_peaceable(&mut self, board: &mut Board) -> Result<&SolverResult, String> {
+        let description = "Backtracking over the cells, peaceable armies".to_string();
+        let original = board.get_signature().clone();
+        let cells = board.get_cells().iter().filter(|c| c.is_empty()).count();
+
+        let mut empty = board.clone();
+        empty.reset();
+
+        let mut best: Option<(usize, Board)> = None;
+        let mut jumps = 0_u32;
+        let mut optimal = true;
+
+        for count in 1..=cells / 2 {
+            let mut attempt = empty.clone();
+            attempt.set_armies(count)?;
+
+            self.solver_result = SolverResult::new(vec![], description.clone());
+            self.solver_result.set_jumps(jumps);
+            let found = self.exec_solve_peaceable(&mut attempt, 0)?;
+            jumps = *self.solver_result.get_jumps();
+
+            if !found {
+                optimal = jumps <= self.max_jumps;
+                break;
+            }
+            best = Some((count, attempt));
+        }
+
+        self.solver_result = SolverResult::new(original, description);
+        self.solver_result.set_jumps(jumps);
+        self.solver_result.set_optimal(optimal);
+
+        if let Some((count, best)) = best {
+            *board = best;
+            self.solver_result.update_best(count, board.get_signature());
+            self.solver_result.set_solved(board.get_signature().clone());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_peaceable(&mut self, board: &mut Board, i: usize) -> Result<bool, String> {
+        if board.is_solved() {
+            return Ok(true);
+        }
+
+        if self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(false);
+        }
+
+        let count = board.get_mixture()[0].1;
+        let white = board.get_army(&Color::White).len();
+        let black = board.get_army(&Color::Black).len();
+
+        // Cells left for each side, and for both
+        let cells = &board.get_cells()[i..];
+        let free = |color: &Color| cells.iter().filter(|c| c.is_available_for(color)).count();
+        let either = cells
+            .iter()
+            .filter(|c| Color::ALL.iter().any(|color| c.is_available_for(color)))
+            .count();
+        if white + free(&Color::White) < count
+            || black + free(&Color::Black) < count
+            || white + black + either < 2 * count
+        {
+            return Ok(false);
+        }
+
+        let cols = *board.get_cols();
+        let (x, y) = (i % cols + 1, i / cols + 1);
+
+        for color in Color::ALL.iter() {
+            let placed = match color {
+                Color::White => white,
+                Color::Black => black,
+            };
+            if placed >= count || (color == &Color::Black && white == 0) {
+                continue;
+            }
+            if !board.get_cell(&x, &y)?.is_available_for(color)
+                || board.toggle_piece(&x, &y, color.get_kind()).is_err()
+            {
+                continue;
+            }
+
+            self.solver_result.inc_jumps();
+            if self.exec_solve_peaceable(board, i + 1)? {
+                return Ok(true);
+            }
+            board.toggle_piece(&x, &y, color.get_kind())?;
+        }
+
+        self.exec_solve_peaceable(board, i + 1)
+    }
+
+    /// Find the fewest pieces attacking every cell they don't occupy
+    ///
+    /// Branch and bound over the first cell left empty, which must be covered by a piece on
+    /// itself or on a cell attacking it, the pieces covering the most empty cells first. A
+    /// branch is dropped when even pieces covering as many cells as the best one can't beat
+    /// the smallest set found. With `independent`, the pieces must not attack each other
+    /// either. The pieces on the board are replaced by the smallest set found, on a board
+    /// allowing attacked placements unless independent, and the result is optimal if the
+    /// search didn't run out of jumps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(6);
+    ///
+    /// let mut solver = Solver::new();
+    /// let result = solver.solve_domination(&mut board, false).unwrap();
+    ///
+    /// assert!(result.is_optimal());
+    /// assert_eq!(board.get_queens().len(), 3);
+    /// assert!(board.is_dominated());
+    ///
+    /// solver.solve_domination(&mut board, true).unwrap();
+    /// assert_eq!(board.get_queens().len(), 4);
+    /// ```
+    pub fn solve_domination(
+        &mut self,
+        board: &mut Board,
+        independent: bool,
+    ) -> Result<&SolverResult, String> {
+        if !board.get_mixture().is_empty() {
+            return Err("Domination places a single kind of piece".to_string());
+        }
+
+        let description = if independent {
+            "Branch and bound, independent domination"
+        } else {
+            "Branch and bound, domination"
+        };
+        self.solver_result =
+            SolverResult::new(board.get_signature().clone(), description.to_string());
+
+        let cells = board.get_cells().len();
+        let mut search = board.clone();
+        search.reset();
+        search.set_mixture(&[])?;
+        search.set_attacked_placements(!independent)?;
+
+        // Cells whose piece would cover each cell, and the most cells a piece covers
+        let holds = |c: &Cell| !c.is_blocked() && !c.is_obstacle();
+        let masks = (0..cells)
+            .map(|p| search.get_attack_mask(p))
+            .collect::<Vec<_>>();
+        let covers = (0..cells)
+            .map(|c| {
+                (0..cells)
+                    .filter(|p| holds(&search.get_cells()[*p]) && (*p == c || masks[*p].get(c)))
+                    .collect::<Vec<usize>>()
+            })
+            .collect::<Vec<_>>();
+        let most = masks.iter().map(|m| m.count_ones() + 1).max().unwrap_or(1);
+
+        let mut placed = vec![];
+        let mut best = None;
+        self.exec_solve_domination(
+            &mut search,
+            (&masks, &covers, most, independent),
+            &mut placed,
+            &mut best,
+        )?;
+
+        self.solver_result
+            .set_optimal(self.solver_result.get_jumps() <= &self.max_jumps);
+
+        if let Some(best) = best {
+            board.reset();
+            board.set_mixture(&[])?;
+            board.set_attacked_placements(!independent)?;
+            board.set_goal(best.len());
+
+            let cols = *board.get_cols();
+            for i in best {
+                board.toggle_cell(&(i % cols + 1), &(i / cols + 1))?;
+            }
+            self.solver_result.set_solved(board.get_signature().clone());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_domination(
+        &mut self,
+        board: &mut Board,
+        (masks, covers, most, independent): (&[BitBoard], &[Vec<usize>], usize, bool),
+        placed: &mut Vec<usize>,
+        best: &mut Option<Vec<usize>>,
+    ) -> Result<(), String> {
+        let empty = board
+            .get_cells()
+            .iter()
+            .filter(|c| c.is_empty())
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+
+        if empty.is_empty() {
+            *best = Some(placed.clone());
+            return Ok(());
+        }
+
+        let limit = best.as_ref().map_or(usize::MAX, |b| b.len());
+        if placed.len() + empty.len().div_ceil(most) >= limit
+            || self.solver_result.get_jumps() > &self.max_jumps
+        {
+            return Ok(());
+        }
+
+        // Candidates by the number of empty cells they would cover
+        let cells = board.get_cells();
+        let mut candidates = covers[empty[0]]
+            .iter()
+            .filter(|p| {
+                if independent {
+                    cells[**p].is_empty()
+                } else {
+                    !cells[**p].is_queen()
+                }
+            })
+            .map(|p| {
+                let covered = masks[*p]
+                    .iter_ones()
+                    .filter(|q| cells[*q].is_empty())
+                    .count()
+                    + cells[*p].is_empty() as usize;
+                (covered, *p)
+            })
+            .collect::<Vec<(usize, usize)>>();
+        candidates.sort_by(|a, b| b.cmp(a));
+
+        let cols = *board.get_cols();
+        for (_, p) in candidates {
+            let (x, y) = (p % cols + 1, p / cols + 1);
+            board.toggle_cell(&x, &y)?;
+            placed.push(p);
+            self.solver_result.inc_jumps();
+
+            self.exec_solve_domination(board, (masks, covers, most, independent), placed, best)?;
+
+            placed.pop();
+            board.toggle_cell(&x, &y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Place one queen per row with the largest total weight
+    ///
+    /// `weights[y - 1][x - 1]` is the weight of the cell `(x, y)`, and the pieces already on
+    /// the board count towards the total. Branch and bound over the rows from the bottom, the
+    /// heaviest cells first: the bound adds to the current weight the heaviest available cell
+    /// of every row left, or of every column left if lower, which never underestimates a
+    /// completion. The board is left with the heaviest solution found, optimal only if the
+    /// search was exhaustive, otherwise its value is just a lower bound of the maximum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let weights = vec![
+    ///     vec![1, 0, 0, 0],
+    ///     vec![0, 0, 0, 5],
+    ///     vec![9, 0, 0, 0],
+    ///     vec![0, 0, 1, 0],
+    /// ];
+    /// let mut board = Board::new(4);
+    ///
+    /// let mut solver = Solver::new();
+    /// let result = solver.solve_weighted(&mut board, &weights).unwrap();
+    ///
+    /// assert!(result.is_solved() && result.is_optimal());
+    /// assert_eq!(result.get_value(), &Some(15));
+    /// assert_eq!(board.to_permutation(), Some(vec![2, 4, 1, 3]));
+    /// ```
+    pub fn solve_weighted(
+        &mut self,
+        board: &mut Board,
+        weights: &[Vec<i64>],
+    ) -> Result<&SolverResult, String> {
+        let (cols, rows) = (*board.get_cols(), *board.get_rows());
+        if weights.len() != rows || weights.iter().any(|w| w.len() != cols) {
+            return Err(format!("Weights must be a {}x{} matrix", cols, rows));
+        }
+        if board.get_goal() != rows {
+            return Err("Weighted queens need one piece to place per row".to_string());
+        }
+
+        self.solver_result = SolverResult::new(
+            board.get_signature().clone(),
+            "Branch and bound over the rows, weighted".to_string(),
+        );
+
+        let value = board
+            .get_queens()
+            .iter()
+            .map(|(x, y)| weights[y - 1][x - 1])
+            .sum();
+        let mut best = None;
+        self.exec_solve_weighted(board, weights, 1, value, &mut best)?;
+
+        self.solver_result
+            .set_optimal(self.solver_result.get_jumps() <= &self.max_jumps);
+
+        if let Some((value, signature)) = best {
+            board.load_signature(&signature)?;
+            self.solver_result.set_value(value);
+            self.solver_result.set_solved(signature);
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_weighted(
+        &mut self,
+        board: &mut Board,
+        weights: &[Vec<i64>],
+        y: usize,
+        value: i64,
+        best: &mut Option<(i64, Vec<u8>)>,
+    ) -> Result<(), String> {
+        if board.is_solved() {
+            if best.as_ref().is_none_or(|(b, _)| value > *b) {
+                *best = Some((value, board.get_signature().clone()));
+            }
+            return Ok(());
+        }
+
+        if y > *board.get_rows() || self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(());
+        }
+
+        let cols = *board.get_cols();
+        if (1..=cols).any(|x| board.get_cell(&x, &y).is_ok_and(|c| c.is_queen())) {
+            return self.exec_solve_weighted(board, weights, y + 1, value, best);
+        }
+
+        let bound = match self.get_weight_bound(board, weights, y) {
+            Some(bound) => bound,
+            None => return Ok(()),
+        };
+        if best.as_ref().is_some_and(|(b, _)| value + bound <= *b) {
+            return Ok(());
+        }
+
+        let mut candidates = (1..=cols)
+            .filter(|x| board.get_cell(x, &y).is_ok_and(|c| c.is_empty()))
+            .collect::<Vec<usize>>();
+        candidates.sort_by_key(|x| -weights[y - 1][x - 1]);
+
+        for x in candidates {
+            board.toggle_cell(&x, &y)?;
+            self.solver_result.inc_jumps();
+
+            let weight = weights[y - 1][x - 1];
+            self.exec_solve_weighted(board, weights, y + 1, value + weight, best)?;
+
+            board.toggle_cell(&x, &y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Most weight the rows from `y` up can add, none if a row has no available cell left
+    fn get_weight_bound(&self, board: &Board, weights: &[Vec<i64>], y: usize) -> Option<i64> {
+        let (cols, rows) = (*board.get_cols(), *board.get_rows());
+        let available = |x: &usize, y: &usize| board.get_cell(x, y).is_ok_and(|c| c.is_empty());
+        let has_queen = |x: &usize, y: &usize| board.get_cell(x, y).is_ok_and(|c| c.is_queen());
+
+        let mut by_rows = 0;
+        for ry in (y..=rows).filter(|ry| !(1..=cols).any(|x| has_queen(&x, ry))) {
+            by_rows += (1..=cols)
+                .filter(|x| available(x, &ry))
+                .map(|x| weights[ry - 1][x - 1])
+                .max()?;
+        }
+
+        // Every queen left goes on a distinct column, on one of the rows left
+        let mut by_columns = (1..=cols)
+            .filter(|x| !(1..=rows).any(|ry| has_queen(x, &ry)))
+            .filter_map(|x| {
+                (y..=rows)
+                    .filter(|ry| available(&x, ry))
+                    .map(|ry| weights[ry - 1][x - 1])
+                    .max()
+            })
+            .collect::<Vec<i64>>();
+        by_columns.sort_by(|a, b| b.cmp(a));
+        let left = (y..=rows)
+            .filter(|ry| !(1..=cols).any(|x| has_queen(&x, ry)))
+            .count();
+        let by_columns = by_columns.iter().take(left).sum();
+
+        Some(by_rows.min(by_columns))
+    }
+
+    /// Solve the board with the built-in SAT solver, instead of the heuristic backtracking
+    ///
+    /// The board and its placed pieces are encoded with `cnf::encode`, see there for the
+    /// boards supported, and each conflict of the SAT solver counts as a jump. Unlike the
+    /// backtracking, a search that ends without a solution proves there is none, the result
+    /// being unsatisfiable, unless it ran out of jumps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::cnf::Encoding;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(8);
+    /// let mut solver = Solver::new();
+    /// assert!(solver.solve_sat(&mut board, &Encoding::Commander).unwrap().is_solved());
+    /// assert!(board.is_solved());
+    ///
+    /// let mut board = Board::new(6);
+    /// board.toggle_cell(&1, &1).unwrap();
+    /// let result = solver.solve_sat(&mut board, &Encoding::Pairwise).unwrap();
+    /// assert!(!result.is_solved());
+    /// assert!(result.is_unsatisfiable());
+    /// ```
+    pub fn solve_sat(
+        &mut self,
+        board: &mut Board,
+        encoding: &Encoding,
+    ) -> Result<&SolverResult, String> {
+        self.solver_result = SolverResult::new(
+            board.get_signature().clone(),
+            format!("CDCL SAT solver, {:?} encoding", encoding),
+        );
+
+        let cnf = cnf::encode(board, encoding)?;
+        let mut sat = SatSolver::from_cnf(&cnf)?;
+        sat.set_max_conflicts(self.max_jumps);
+
+        let outcome = sat.solve();
+        self.solver_result.set_jumps(*sat.get_conflicts());
+
+        match outcome {
+            Ok(Some(model)) => {
+                *board = cnf::decode(board, &model)?;
+                self.solver_result.set_solved(board.get_signature().clone());
+            }
+            Ok(None) => self.solver_result.set_unsatisfiable(true),
+            Err(_) => {}
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    /// Color the cells with the fewest colors, no two cells of a color attacking each other
+    ///
+    /// The pieces on the board are ignored, and holes and obstacles get no color. The number
+    /// of colors starts at the size of a set of cells attacking each other, found greedily,
+    /// and rises up to the colors of a greedy coloring. The modular coloring `(2x + y) % n`
+    /// of a square board is taken right away when it works, as for the queens when `n` is
+    /// coprime with 6, where the board splits into `n` disjoint solutions. Otherwise every
+    /// number of colors is searched with DSATUR backtracking, coloring first the cell with the
+    /// most colors among the cells it attacks.
+    ///
+    /// The solution is the signature of a `Coloring` and the value its number of colors,
+    /// optimal unless a search with fewer colors ran out of jumps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::coloring::Coloring;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let board = Board::new(5);
+    ///
+    /// let mut solver = Solver::new();
+    /// let result = solver.solve_coloring(&board).unwrap();
+    ///
+    /// assert!(result.is_optimal());
+    /// assert_eq!(result.get_value(), &Some(5));
+    ///
+    /// let coloring = Coloring::from_signature(5, 5, result.get_solution().as_ref().unwrap());
+    /// assert!(coloring.unwrap().splits_into_solutions());
+    /// ```
+    pub fn solve_coloring(&mut self, board: &Board) -> Result<&SolverResult, String> {
+        self.solver_result = SolverResult::new(
+            board.get_signature().clone(),
+            "DSATUR backtracking over the colors".to_string(),
+        );
+
+        let mut empty = board.clone();
+        empty.reset();
+        empty.set_mixture(&[])?;
+
+        let (cols, rows) = (*board.get_cols(), *board.get_rows());
+        let cells = cols * rows;
+        let vertices = empty
+            .get_cells()
+            .iter()
+            .filter(|c| !c.is_blocked() && !c.is_obstacle())
+            .map(|c| *c.get_xyi().2)
+            .collect::<Vec<usize>>();
+
+        // Cells in conflict when either one attacks the other
+        let mut masks = (0..cells)
+            .map(|i| empty.get_attack_mask(i))
+            .collect::<Vec<BitBoard>>();
+        for i in 0..cells {
+            for j in masks[i].iter_ones().collect::<Vec<usize>>() {
+                masks[j].set(i);
+            }
+        }
+        let adjacency = (0..cells)
+            .map(|i| {
+                masks[i]
+                    .iter_ones()
+                    .filter(|j| vertices.contains(j))
+                    .collect()
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let lower = get_greedy_clique(&vertices, &adjacency, &masks);
+        let greedy = get_greedy_coloring(cells, &vertices, &adjacency);
+        let upper = greedy.iter().flatten().max().map_or(0, |c| c + 1);
+
+        let mut best = (upper, greedy);
+        let mut optimal = lower == upper;
+
+        let modular = (0..cells)
+            .map(|i| Some((2 * (i % cols) + i / cols) % cols.max(1)))
+            .zip(0..cells)
+            .map(|(c, i)| if vertices.contains(&i) { c } else { None })
+            .collect::<Vec<Option<usize>>>();
+        let is_proper = |colors: &[Option<usize>]| {
+            vertices
+                .iter()
+                .all(|v| adjacency[*v].iter().all(|u| colors[*u] != colors[*v]))
+        };
+        if cols == rows && cols < best.0 && is_proper(&modular) {
+            best = (cols, modular);
+            optimal = lower == cols;
+        }
+
+        if !optimal {
+            optimal = true;
+            for k in lower..best.0 {
+                let mut colors = vec![None; cells];
+                let mut seen = vec![vec![0_u16; k]; cells];
+
+                if self.exec_solve_coloring(&adjacency, &vertices, k, &mut colors, &mut seen, 0)? {
+                    best = (k, colors);
+                    break;
+                }
+                if self.solver_result.get_jumps() > &self.max_jumps {
+                    optimal = false;
+                }
+            }
+        }
+
+        let coloring = Coloring::new(cols, rows, best.1)?;
+        self.solver_result.set_value(best.0 as i64);
+        self.solver_result.set_optimal(optimal);
+        self.solver_result.set_solved(coloring.get_signature());
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_coloring(
+        &mut self,
+        adjacency: &[Vec<usize>],
+        vertices: &[usize],
+        k: usize,
+        colors: &mut Vec<Option<usize>>,
+        seen: &mut Vec<Vec<u16>>,
+        used: usize,
+    ) -> Result<bool, String> {
+        // Uncolored cell seeing the most colors, then with the most conflicts
+        let v = vertices
+            .iter()
+            .filter(|v| colors[**v].is_none())
+            .max_by_key(|v| {
+                let saturation = seen[**v].iter().filter(|s| **s > 0).count();
+                (saturation, adjacency[**v].len())
+            });
+        let v = match v {
+            Some(v) => *v,
+            None => return Ok(true),
+        };
+
+        if self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(false);
+        }
+
+        // A new color is only tried once, as the colors are interchangeable
+        for c in 0..k.min(used + 1) {
+            if seen[v][c] > 0 {
+                continue;
+            }
+
+            colors[v] = Some(c);
+            adjacency[v].iter().for_each(|u| seen[*u][c] += 1);
+            self.solver_result.inc_jumps();
+
+            if self.exec_solve_coloring(adjacency, vertices, k, colors, seen, used.max(c + 1))? {
+                return Ok(true);
+            }
+
+            colors[v] = None;
+            adjacency[v].iter().for_each(|u| seen[*u][c] -= 1);
+        }
+
+        Ok(false)
+    }
+
+    /// Place one queen per row with no three pieces on any straight line
+    ///
+    /// Rows are filled from the bottom, and the cells in line with two placed pieces are
+    /// blocked incrementally through `CollinearCells`, with exact integer steps for every
+    /// slope. The candidates of a row are tried from its middle outwards. The heuristics and
+    /// goal don't apply to this mode, the board must have one piece to place per row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::board::Board;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// let mut board = Board::new(8);
+    ///
+    /// let mut solver = Solver::new();
+    /// assert!(solver.solve_no_three_in_line(&mut board).unwrap().is_solved());
+    /// assert_eq!(board.get_queens().len(), 8);
+    ///
+    /// let mut board = Board::new(7);
+    /// assert!(!solver.solve_no_three_in_line(&mut board).unwrap().is_solved());
+    /// ```
+    pub fn solve_no_three_in_line(&mut self, board: &mut Board) -> Result<&SolverResult, String> {
+        if board.get_goal() != *board.get_rows() {
+            return Err("No three in line needs one piece to place per row".to_string());
+        }
+
+        self.solver_result = SolverResult::new(
+            board.get_signature().clone(),
+            "Backtracking over the rows, no three in line".to_string(),
+        );
+
+        let mut lines = CollinearCells::new(*board.get_cols(), *board.get_rows());
+        let queens = board.get_queens();
+        for (x, y) in &queens {
+            if lines.is_blocked(x, y) {
+                return Ok(&self.solver_result);
+            }
+            lines.toggle(x, y)?;
+        }
+
+        if self.exec_solve_no_three_in_line(board, &mut lines, 1)? {
+            self.solver_result.set_solved(board.get_signature().clone());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    fn exec_solve_no_three_in_line(
+        &mut self,
+        board: &mut Board,
+        lines: &mut CollinearCells,
+        y: usize,
+    ) -> Result<bool, String> {
+        if y > *board.get_rows() {
+            return Ok(board.is_solved());
+        }
+
+        if self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(false);
+        }
+
+        let cols = *board.get_cols();
+        if (1..=cols).any(|x| board.get_cell(&x, &y).is_ok_and(|c| c.is_queen())) {
+            return self.exec_solve_no_three_in_line(board, lines, y + 1);
+        }
+
+        let mut candidates = (1..=cols)
+            .filter(|x| board.get_cell(x, &y).is_ok_and(|c| c.is_empty()))
+            .filter(|x| !lines.is_blocked(x, &y))
+            .filter(|x| self.constraints.iter().all(|k| k.allows(board, x, &y)))
+            .collect::<Vec<usize>>();
+        candidates.sort_by_key(|x| (2 * x).abs_diff(cols + 1));
+
+        for x in candidates {
+            board.toggle_cell(&x, &y)?;
+            lines.toggle(&x, &y)?;
+            self.solver_result.inc_jumps();
+
+            if self.exec_solve_no_three_in_line(board, lines, y + 1)? {
+                return Ok(true);
+            }
+
+            lines.toggle(&x, &y)?;
+            board.toggle_cell(&x, &y)?;
+        }
+
+        Ok(false)
+    }
+
+    /// Place the stars of a region puzzle, leaving the stars already on the board
+    ///
+    /// The cells are decided row by row from the bottom, and a branch is dropped as soon as
+    /// a row is left without its stars, or a region without room for the stars it misses. The
+    /// heuristics don't apply to these boards.
+    pub fn solve_regions(&mut self, board: &mut RegionBoard) -> Result<&SolverResult, String> {
+        self.solver_result = SolverResult::new(
+            board.get_signature(),
+            "Backtracking over the regions".to_string(),
+        );
+
+        let mut solution = None;
+        self.exec_solve_regions(board, 0, &mut |b| {
+            solution = Some(b.clone());
+            true
+        })?;
+
+        if let Some(solution) = solution {
+            *board = solution;
+            self.solver_result.set_solved(board.get_signature());
+        }
+
+        Ok(&self.solver_result)
+    }
+
+    /// Count the solutions of a region puzzle, stopping once `limit` are found
+    ///
+    /// Fails if the search is cut short by `max_jumps`, as the count would be a guess.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::region::RegionBoard;
+    /// use andaluz_core::solver::Solver;
+    ///
+    /// // The mirror image of any solution is another one
+    /// let board = RegionBoard::from_letters("AABB;AABB;CCDD;CCDD").unwrap();
+    ///
+    /// let mut solver = Solver::new();
+    /// assert_eq!(solver.count_regions(&board, 10).unwrap(), 2);
+    /// assert!(!solver.has_unique_solution(&board).unwrap());
+    /// ```
+    pub fn count_regions(&mut self, board: &RegionBoard, limit: usize) -> Result<usize, String> {
+        self.solver_result = SolverResult::new(
+            board.get_signature(),
+            "Backtracking over the regions".to_string(),
+        );
+
+        let mut count = 0;
+        let mut search = board.clone();
+        let complete = !self.exec_solve_regions(&mut search, 0, &mut |_| {
+            count += 1;
+            count >= limit
+        })?;
+
+        if complete && self.solver_result.get_jumps() > &self.max_jumps {
+            return Err(format!(
+                "Search cut short after {} jumps",
+                self.solver_result.get_jumps()
+            ));
+        }
+
+        Ok(count)
+    }
+
+    /// Whether a region puzzle has exactly one solution
+    pub fn has_unique_solution(&mut self, board: &RegionBoard) -> Result<bool, String> {
+        Ok(self.count_regions(board, 2)? == 1)
+    }
+
+    /// Decide the cells from `i` on, calling `found` on every solution until it returns true
+    fn exec_solve_regions<F>(
+        &mut self,
+        board: &mut RegionBoard,
+        i: usize,
+        found: &mut F,
+    ) -> Result<bool, String>
+    where
+        F: FnMut(&RegionBoard) -> bool,
+    {
+        if self.solver_result.get_jumps() > &self.max_jumps {
+            return Ok(false);
+        }
+
+        let side = *board.get_side();
+        let stars = *board.get_stars_per_region();
+        let (x, y) = (i % side + 1, i / side + 1);
+        let placed = board.get_stars();
+
+        if x == 1 && !self.has_room_for_regions(board, &placed, y) {
+            return Ok(false);
+        }
+
+        if i == side * side {
+            return Ok(board.is_solved() && found(board));
+        }
+
+        // Stars of the row, and whether the rest of the row can still complete it
+        let in_row = placed.iter().filter(|(_, sy)| *sy == y).count();
+        if in_row + (side - x + 1).div_ceil(2) < stars {
+            return Ok(false);
+        }
+
+        if board.is_star(&x, &y) {
+            return self.exec_solve_regions(board, i + 1, found);
+        }
+
+        if in_row < stars && board.allows(&x, &y) {
+            board.toggle_cell(&x, &y)?;
+            self.solver_result.inc_jumps();
+
+            let stop = self.exec_solve_regions(board, i + 1, found)?;
+            board.toggle_cell(&x, &y)?;
+
+            if stop {
+                return Ok(true);
+            }
+        }
+
+        if x == side && in_row < stars {
+            return Ok(false);
+        }
+
+        self.exec_solve_regions(board, i + 1, found)
+    }
+
+    /// Whether every region still has enough cells from the row `y` up for its stars
+    fn has_room_for_regions(
+        &self,
+        board: &RegionBoard,
+        placed: &[(usize, usize)],
+        y: usize,
+    ) -> bool {
+        let side = *board.get_side();
+        let stars = *board.get_stars_per_region();
+
+        board.get_letters().iter().all(|letter| {
+            let missing = stars
+                - placed
+                    .iter()
+                    .filter(|(sx, sy)| board.get_region(sx, sy) == Ok(letter))
+                    .count();
+            let rows = (y..=side)
+                .filter(|ry| (1..=side).any(|x| board.get_region(&x, ry) == Ok(letter)))
+                .count();
+            missing <= rows * stars
+        })
     }
 
     fn exec_solve(&mut self, board: &mut Board) -> Result<&SolverResult, String> {
@@ -138,49 +1286,549 @@ impl<'a> Solver<'a> {
                 return Ok(&self.solver_result);
             }
 
-            let cells = board.get_available_cells();
+            // Branch on the cell and, when mixing pieces, on the kind placed there
+            let placements = board.get_available_placements();
             let mut nodes = vec![];
-            for c in cells {
+            for (c, kind) in placements {
                 let (x, y, _) = c.get_xyi();
-                board.toggle_cell(&x, &y)?;
+                if !self.constraints.iter().all(|k| k.allows(board, x, y)) {
+                    continue;
+                }
+
+                board.toggle_piece(x, y, kind)?;
+                self.solver_result
+                    .update_best(self.get_progress(board), board.get_signature());
 
-                if board.is_solved() {
+                if self.is_reached(board) {
                     self.solver_result.inc_jumps();
                     self.solver_result.set_solved(board.get_signature().clone());
                     return Ok(&self.solver_result);
                 }
 
-                let score = self.classifier.score(board, &x, &y);
-                board.toggle_cell(&x, &y)?;
+                let score = self.classifier.score(board, x, y);
+                board.toggle_piece(x, y, kind)?;
 
-                let node = SolutionNode::new(*x, *y, score);
+                let node = SolutionNode::with_kind(*x, *y, kind, score);
                 nodes.push(node);
             }
 
             nodes.sort_by(|a, b| b.partial_cmp(a).unwrap());
 
             for n in nodes {
-                board.toggle_cell(&n.x, &n.y)?;
+                board.toggle_piece(&n.x, &n.y, n.kind)?;
 
                 if !self.depleted_signatures.contains(board.get_signature()) {
                     self.solver_result.inc_jumps();
                     self.exec_solve(board)?;
 
-                    if board.is_solved() {
+                    if self.is_reached(board) {
                         self.solver_result.set_solved(board.get_signature().clone());
                         return Ok(&self.solver_result);
                     }
 
+                    // Boards equivalent for the goal are explored as well
                     // TODO - Should be async
-                    board.get_equivalent_signatures()?.iter().for_each(|s| {
-                        self.depleted_signatures.insert(s.clone());
-                    });
+                    let symmetries = board.get_symmetries();
+                    let signatures = board.get_equivalent_signatures()?;
+                    for (symmetry, s) in symmetries.iter().zip(signatures) {
+                        if self.goal.as_ref().is_none_or(|g| g.is_invariant(symmetry))
+                            && self
+                                .constraints
+                                .iter()
+                                .all(|k| k.is_invariant(board, symmetry))
+                        {
+                            self.depleted_signatures.insert(s);
+                        }
+                    }
                 }
 
-                board.toggle_cell(&n.x, &n.y)?;
+                board.toggle_piece(&n.x, &n.y, n.kind)?;
             }
         }
 
         Ok(&self.solver_result)
     }
 }
+
+/// Size of a set of cells all attacking each other, grown greedily from every cell
+fn get_greedy_clique(vertices: &[usize], adjacency: &[Vec<usize>], masks: &[BitBoard]) -> usize {
+    vertices
+        .iter()
+        .map(|v| {
+            let mut neighbours = adjacency[*v].clone();
+            neighbours.sort_by_key(|u| std::cmp::Reverse(adjacency[*u].len()));
+
+            let mut clique = vec![*v];
+            for u in neighbours {
+                if clique.iter().all(|w| masks[*w].get(u)) {
+                    clique.push(u);
+                }
+            }
+            clique.len()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// DSATUR coloring without backtracking, each cell taking the first color it can
+fn get_greedy_coloring(
+    cells: usize,
+    vertices: &[usize],
+    adjacency: &[Vec<usize>],
+) -> Vec<Option<usize>> {
+    let mut colors = vec![None; cells];
+
+    for _ in vertices {
+        let v = *vertices
+            .iter()
+            .filter(|v| colors[**v].is_none())
+            .max_by_key(|v| {
+                let mut seen = adjacency[**v]
+                    .iter()
+                    .filter_map(|u| colors[*u])
+                    .collect::<Vec<usize>>();
+                seen.sort_unstable();
+                seen.dedup();
+                (seen.len(), adjacency[**v].len())
+            })
+            .expect("A cell is left to color");
+
+        colors[v] = (0..).find(|c| adjacency[v].iter().all(|u| colors[*u] != Some(*c)));
+    }
+
+    colors
+}
+
+impl<'a> Default for Solver<'a> {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::BitBoard;
+
+    #[test]
+    fn solve_with_holes() {
+        let mut board = Board::new(4);
+        board.toggle_hole(&2, &1).unwrap();
+
+        let mut solver = Solver::new();
+        assert!(solver.solve(&mut board).unwrap().is_solved());
+        assert_eq!(board.to_permutation(), Some(vec![3, 1, 4, 2]));
+
+        let mut board = Board::new(4);
+        board.toggle_hole(&2, &1).unwrap();
+        board.toggle_hole(&3, &1).unwrap();
+
+        let mut solver = Solver::new();
+        assert!(!solver.solve(&mut board).unwrap().is_solved());
+    }
+
+    #[test]
+    fn independence_numbers() {
+        use crate::piece::Piece;
+
+        let expected = [
+            (Piece::rook(), 5, 5),
+            (Piece::bishop(), 4, 6),
+            (Piece::king(), 4, 4),
+            (Piece::knight(), 4, 8),
+        ];
+
+        for (piece, cols, count) in expected.iter() {
+            let mut board = Board::new(*cols);
+            board.set_piece(piece.clone()).unwrap();
+
+            let mut solver = Solver::new();
+            assert!(solver.solve_maximum(&mut board).unwrap().is_solved());
+            assert_eq!(board.get_queens().len(), *count, "{} on {}", piece, cols);
+        }
+    }
+
+    #[test]
+    fn maximum_with_obstacles() {
+        let mut board = Board::new(4);
+        board.toggle_obstacle(&2, &2).unwrap();
+        board.toggle_obstacle(&3, &3).unwrap();
+        assert_eq!(board.get_piece_bound(), 6);
+
+        let mut solver = Solver::new();
+        let result = solver.solve_maximum(&mut board).unwrap();
+        assert!(result.is_solved() && result.is_optimal());
+        assert_eq!(board.get_queens().len(), 4);
+        assert!(board.is_solved());
+
+        let mut board = Board::new(6);
+        board.toggle_obstacle(&3, &2).unwrap();
+        assert_eq!(board.get_piece_bound(), 7);
+
+        let mut solver = Solver::new();
+        solver.solve_maximum(&mut board).unwrap();
+        assert_eq!(board.get_queens().len(), 7);
+
+        let mut board = Board::new(5);
+        let mut solver = Solver::new();
+        solver.solve_maximum(&mut board).unwrap();
+        assert_eq!(board.get_queens().len(), 5);
+
+        // Out of jumps, the placement found is only a lower bound
+        let mut board = Board::new(6);
+        board.toggle_obstacle(&3, &2).unwrap();
+        let mut solver = Solver::new();
+        solver.set_max_jumps(10);
+        let result = solver.solve_maximum(&mut board).unwrap();
+        assert!(result.is_solved() && !result.is_optimal());
+        assert!(board.get_queens().len() < 7);
+    }
+
+    #[test]
+    fn cube_queens() {
+        // Most queens of the n x n x n cube, from 2 to 4
+        for (side, count) in [(2, 1), (3, 4), (4, 7)].iter() {
+            let mut board = BoardND::new(*side, 3).unwrap();
+            board.set_goal(*count);
+
+            let mut solver = Solver::new();
+            assert!(solver.solve_nd(&mut board).unwrap().is_solved());
+
+            let mut board = BoardND::new(*side, 3).unwrap();
+            board.set_goal(count + 1);
+            let result = solver.solve_nd(&mut board).unwrap();
+            assert!(!result.is_solved());
+            assert!(result.get_jumps() < &100000);
+        }
+    }
+
+    #[test]
+    fn mixed_pieces() {
+        use crate::piece::Piece;
+
+        let mut board = Board::new(8);
+        board
+            .set_mixture(&[
+                (Piece::queen(), 2),
+                (Piece::rook(), 3),
+                (Piece::knight(), 4),
+            ])
+            .unwrap();
+
+        let mut solver = Solver::new();
+        let result = solver.solve(&mut board).unwrap();
+        assert!(result.is_solved());
+        assert_eq!(result.get_solution().as_ref().unwrap().len(), 3 * 8);
+
+        let placement = board.get_placement();
+        assert_eq!(placement.len(), 9);
+        for (x, y, piece) in &placement {
+            let i = x - 1 + 8 * (y - 1);
+            let mut attacked = piece.attack_mask(8, 8, i, &BitBoard::new(64));
+            attacked.intersect_with(board.get_occupancy());
+            assert_eq!(attacked.count_ones(), 0, "{} at ({}, {})", piece, x, y);
+        }
+
+        // Two rooks and a bishop don't fit on a 2x2 board, whatever the order
+        let mut board = Board::new(2);
+        board
+            .set_mixture(&[(Piece::rook(), 2), (Piece::bishop(), 1)])
+            .unwrap();
+        assert!(!solver.solve(&mut board).unwrap().is_solved());
+    }
+
+    #[test]
+    fn no_three_in_line() {
+        for cols in 1..=20 {
+            let mut board = Board::new(cols);
+            let mut solver = Solver::new();
+            let result = solver.solve_no_three_in_line(&mut board).unwrap();
+
+            if [2, 3, 5, 6, 7].contains(&cols) {
+                assert!(!result.is_solved(), "{} columns", cols);
+                assert!(result.get_jumps() < &100000);
+                continue;
+            }
+            assert!(result.is_solved(), "{} columns", cols);
+            assert!(board.is_solved());
+
+            // Every triple of queens, by the cross product of their offsets
+            let queens = board.get_queens();
+            for (i, a) in queens.iter().enumerate() {
+                for (j, b) in queens.iter().enumerate().skip(i + 1) {
+                    for c in queens.iter().skip(j + 1) {
+                        let (ux, uy) = (b.0 as i64 - a.0 as i64, b.1 as i64 - a.1 as i64);
+                        let (vx, vy) = (c.0 as i64 - a.0 as i64, c.1 as i64 - a.1 as i64);
+                        assert_ne!(ux * vy, uy * vx, "{:?} {:?} {:?}", a, b, c);
+                    }
+                }
+            }
+        }
+
+        let mut board = Board::new_rectangle(4, 5);
+        assert!(Solver::new().solve_no_three_in_line(&mut board).is_err());
+    }
+
+    #[test]
+    fn star_battle() {
+        let grid = "ECGGGHHB;ECGGDHBB;EEEDDFFB;BEFDFFFA;BBFFFFAA;BBFFGGAA;DDCCHAAA;DDCCHAEE";
+        let mut board = RegionBoard::from_letters(grid).unwrap();
+        board.set_stars_per_region(2).unwrap();
+
+        let mut solver = Solver::new();
+        assert!(solver.has_unique_solution(&board).unwrap());
+        assert!(solver.solve_regions(&mut board).unwrap().is_solved());
+
+        let stars = board.get_stars();
+        assert_eq!(stars.len(), 16);
+        for (k, a) in stars.iter().enumerate() {
+            for b in &stars[k + 1..] {
+                assert!(a.0.abs_diff(b.0) > 1 || a.1.abs_diff(b.1) > 1);
+            }
+        }
+        for letter in board.get_letters() {
+            let count = stars
+                .iter()
+                .filter(|(x, y)| board.get_region(x, y) == Ok(letter))
+                .count();
+            assert_eq!(count, 2, "region {}", letter);
+        }
+        for line in 1..=8 {
+            assert_eq!(stars.iter().filter(|(x, _)| *x == line).count(), 2);
+            assert_eq!(stars.iter().filter(|(_, y)| *y == line).count(), 2);
+        }
+
+        // A given star that isn't part of the solution leaves none
+        let mut board = RegionBoard::from_letters(grid).unwrap();
+        board.set_stars_per_region(2).unwrap();
+        board.toggle_cell(&1, &1).unwrap();
+        assert!(!solver.solve_regions(&mut board).unwrap().is_solved());
+        assert_eq!(solver.count_regions(&board, 2).unwrap(), 0);
+
+        solver.set_max_jumps(10);
+        let board = RegionBoard::from_letters(grid).unwrap();
+        assert!(solver.count_regions(&board, 2).is_err());
+    }
+
+    #[test]
+    fn peaceable_queens() {
+        // Largest armies of peaceable queens, from 2 to 6 columns
+        for (cols, count) in [(2, 0), (3, 1), (4, 2), (5, 4), (6, 5)].iter() {
+            let mut board = Board::new(*cols);
+            let mut solver = Solver::new();
+            solver.set_max_jumps(u32::MAX);
+            let result = solver.solve_peaceable(&mut board).unwrap();
+
+            assert_eq!(result.is_solved(), *count > 0, "{} columns", cols);
+            assert_eq!(result.get_best_progress(), count, "{} columns", cols);
+            assert!(result.is_optimal(), "{} columns", cols);
+            if *count == 0 {
+                continue;
+            }
+
+            let white = board.get_army(&Color::White);
+            let black = board.get_army(&Color::Black);
+            assert_eq!((white.len(), black.len()), (*count, *count));
+            for (wx, wy) in &white {
+                for (bx, by) in &black {
+                    assert!(wx != bx && wy != by && wx.abs_diff(*bx) != wy.abs_diff(*by));
+                }
+            }
+        }
+
+        // Out of jumps, the best armies found so far are kept
+        let mut board = Board::new(6);
+        let mut solver = Solver::new();
+        solver.set_max_jumps(20);
+        let result = solver.solve_peaceable(&mut board).unwrap();
+        assert!(!result.is_optimal());
+        assert!(result.get_best_progress() < &5);
+        assert_eq!(
+            board.get_army(&Color::White).len(),
+            *result.get_best_progress()
+        );
+    }
+
+    #[test]
+    fn domination_numbers() {
+        // Domination and independent domination numbers of the queens, from 1 to 8 columns
+        let expected = [1, 1, 1, 2, 3, 3, 4, 5];
+        let independent = [1, 1, 1, 3, 3, 4, 4, 5];
+
+        for cols in 1..=8 {
+            for (numbers, is_independent) in [(&expected, false), (&independent, true)].iter() {
+                let mut board = Board::new(cols);
+                let mut solver = Solver::new();
+                solver.set_max_jumps(u32::MAX);
+                let result = solver
+                    .solve_domination(&mut board, *is_independent)
+                    .unwrap();
+
+                assert!(result.is_solved() && result.is_optimal());
+                assert_eq!(
+                    board.get_queens().len(),
+                    numbers[cols - 1],
+                    "{} columns",
+                    cols
+                );
+                assert!(board.is_dominated());
+                assert!(board.is_solved());
+                assert_eq!(board.allows_attacked_placements(), !is_independent);
+            }
+        }
+
+        // Out of jumps, a dominating set is still found but not proven smallest
+        let mut board = Board::new(8);
+        let mut solver = Solver::new();
+        solver.set_max_jumps(10);
+        let result = solver.solve_domination(&mut board, false).unwrap();
+        assert!(result.is_solved() && !result.is_optimal());
+        assert!(board.is_dominated());
+    }
+
+    #[test]
+    fn weighted_queens() {
+        use crate::counter::solutions;
+        use crate::rng::Rng;
+
+        let mut rng = Rng::new(21);
+        for cols in [6, 8].iter() {
+            let weights = (0..*cols)
+                .map(|_| (0..*cols).map(|_| rng.below(100) as i64 - 20).collect())
+                .collect::<Vec<Vec<i64>>>();
+
+            // Heaviest of every solution
+            let heaviest = solutions(*cols)
+                .unwrap()
+                .map(|p| {
+                    p.iter()
+                        .enumerate()
+                        .map(|(y, x)| weights[y][x - 1])
+                        .sum::<i64>()
+                })
+                .max();
+
+            let mut board = Board::new(*cols);
+            let mut solver = Solver::new();
+            let result = solver.solve_weighted(&mut board, &weights).unwrap();
+
+            assert!(result.is_solved() && result.is_optimal());
+            assert_eq!(result.get_value(), &heaviest);
+            let total = board
+                .get_queens()
+                .iter()
+                .map(|(x, y)| weights[y - 1][x - 1])
+                .sum::<i64>();
+            assert_eq!(Some(total), heaviest);
+        }
+
+        // Out of jumps, the best solution so far is kept
+        let weights = (1..=14)
+            .map(|y| (1..=14).map(|x| (x * y % 11) as i64).collect())
+            .collect::<Vec<Vec<i64>>>();
+        let mut board = Board::new(14);
+        let mut solver = Solver::new();
+        solver.set_max_jumps(500);
+        let result = solver.solve_weighted(&mut board, &weights).unwrap();
+        assert!(result.is_solved() && !result.is_optimal());
+        assert!(board.is_solved());
+        let total = board
+            .get_queens()
+            .iter()
+            .map(|(x, y)| weights[y - 1][x - 1])
+            .sum::<i64>();
+        assert_eq!(result.get_value(), &Some(total));
+
+        let mut board = Board::new(4);
+        assert!(solver.solve_weighted(&mut board, &[vec![0; 4]]).is_err());
+    }
+
+    #[test]
+    fn queen_graph_coloring() {
+        use crate::coloring::Coloring;
+
+        // Chromatic numbers of the queen graphs, from 1 to 7 columns
+        let expected = [1, 4, 5, 5, 5, 7, 7];
+
+        for cols in 1..=7 {
+            let board = Board::new(cols);
+            let mut solver = Solver::new();
+            solver.set_max_jumps(u32::MAX);
+            let result = solver.solve_coloring(&board).unwrap();
+
+            assert!(result.is_optimal(), "{} columns", cols);
+            assert_eq!(result.get_value(), &Some(expected[cols - 1] as i64));
+
+            let solution = result.get_solution().as_ref().unwrap();
+            let coloring = Coloring::from_signature(cols, cols, solution).unwrap();
+            assert_eq!(coloring.get_color_count(), expected[cols - 1]);
+            assert_eq!(coloring.splits_into_solutions(), expected[cols - 1] == cols);
+
+            // Every color is a set of non-attacking queens
+            for signature in coloring.get_signatures() {
+                let mut board = Board::new(cols);
+                board.set_goal(0);
+                board.load_signature(&signature).unwrap();
+            }
+        }
+
+        let board = Board::new(11);
+        let mut solver = Solver::new();
+        let result = solver.solve_coloring(&board).unwrap();
+        assert_eq!(result.get_jumps(), &0);
+        assert_eq!(result.get_value(), &Some(11));
+    }
+
+    #[test]
+    fn sat_completions() {
+        use crate::cnf::Encoding;
+
+        let encodings = [
+            Encoding::Pairwise,
+            Encoding::SequentialCounter,
+            Encoding::Commander,
+        ];
+
+        for encoding in &encodings {
+            for cols in [1, 4, 5, 8, 12, 30] {
+                let mut board = Board::new(cols);
+                let mut solver = Solver::new();
+                let result = solver.solve_sat(&mut board, encoding).unwrap();
+                assert!(result.is_solved(), "{} columns", cols);
+                assert!(board.is_solved());
+            }
+
+            for cols in [2, 3] {
+                let mut board = Board::new(cols);
+                let mut solver = Solver::new();
+                assert!(solver
+                    .solve_sat(&mut board, encoding)
+                    .unwrap()
+                    .is_unsatisfiable());
+            }
+
+            // No 8-queens solution holds both (1, 1) and (2, 3)
+            let mut board = Board::new(8);
+            board.toggle_cell(&1, &1).unwrap();
+            board.toggle_cell(&2, &3).unwrap();
+            let mut solver = Solver::new();
+            let result = solver.solve_sat(&mut board, encoding).unwrap();
+            assert!(!result.is_solved());
+            assert!(result.is_unsatisfiable());
+
+            let mut board = Board::new(10);
+            board.toggle_cell(&1, &1).unwrap();
+            board.toggle_hole(&5, &5).unwrap();
+            let mut solver = Solver::new();
+            assert!(solver.solve_sat(&mut board, encoding).unwrap().is_solved());
+            assert!(board.is_solved());
+            assert!(board.get_queens().contains(&(1, 1)));
+        }
+
+        let mut board = Board::new(30);
+        let mut solver = Solver::new();
+        solver.set_max_jumps(0);
+        let result = solver.solve_sat(&mut board, &Encoding::Pairwise).unwrap();
+        assert!(!result.is_solved() && !result.is_unsatisfiable());
+    }
+}
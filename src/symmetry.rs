@@ -0,0 +1,343 @@
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::counter::{solutions, Solutions};
+
+/// Transforms of the square, the dihedral group D4
+///
+/// Rotations are counterclockwise, with `y` growing upwards as printed by
+/// `Board::to_multiline_string`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Swap around the diagonal from lower left to top right
+    ReflectDiagonal,
+    /// Swap the left and right sides
+    ReflectVertical,
+    /// Swap around the diagonal from top left to lower right
+    ReflectAntiDiagonal,
+    /// Swap the top and bottom sides
+    ReflectHorizontal,
+}
+
+impl Symmetry {
+    /// Every transform, in the order used by `Board::get_equivalent_signatures`
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::ReflectDiagonal,
+        Symmetry::ReflectVertical,
+        Symmetry::ReflectAntiDiagonal,
+        Symmetry::ReflectHorizontal,
+    ];
+
+    /// Transforms that keep the dimensions of a rectangular board
+    pub const RECTANGLE: [Symmetry; 4] = [
+        Symmetry::Identity,
+        Symmetry::Rotate180,
+        Symmetry::ReflectVertical,
+        Symmetry::ReflectHorizontal,
+    ];
+
+    /// Symmetry group of a board with the given dimensions
+    pub fn group(cols: usize, rows: usize) -> &'static [Symmetry] {
+        if cols == rows {
+            &Symmetry::ALL
+        } else {
+            &Symmetry::RECTANGLE
+        }
+    }
+
+    /// Map the coordinates of a cell of a board with `cols` columns and `rows` rows
+    ///
+    /// The quarter turns and diagonal reflections swap the dimensions, so they only map
+    /// square boards onto themselves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::symmetry::Symmetry;
+    ///
+    /// assert_eq!(Symmetry::Rotate90.apply(8, 8, &1, &1), (8, 1));
+    /// assert_eq!(Symmetry::ReflectHorizontal.apply(8, 8, &1, &1), (1, 8));
+    /// assert_eq!(Symmetry::Rotate180.apply(5, 3, &1, &1), (5, 3));
+    /// ```
+    pub fn apply(&self, cols: usize, rows: usize, x: &usize, y: &usize) -> (usize, usize) {
+        let (x, y) = (*x, *y);
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (rows + 1 - y, x),
+            Symmetry::Rotate180 => (cols + 1 - x, rows + 1 - y),
+            Symmetry::Rotate270 => (y, cols + 1 - x),
+            Symmetry::ReflectDiagonal => (y, x),
+            Symmetry::ReflectVertical => (cols + 1 - x, y),
+            Symmetry::ReflectAntiDiagonal => (rows + 1 - y, cols + 1 - x),
+            Symmetry::ReflectHorizontal => (x, rows + 1 - y),
+        }
+    }
+
+    /// Map a displacement between two cells, such as the move of a piece
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::symmetry::Symmetry;
+    ///
+    /// assert_eq!(Symmetry::Rotate90.apply_offset(1, 2), (-2, 1));
+    /// assert_eq!(Symmetry::ReflectVertical.apply_offset(1, 2), (-1, 2));
+    /// ```
+    pub fn apply_offset(&self, dx: i64, dy: i64) -> (i64, i64) {
+        match self {
+            Symmetry::Identity => (dx, dy),
+            Symmetry::Rotate90 => (-dy, dx),
+            Symmetry::Rotate180 => (-dx, -dy),
+            Symmetry::Rotate270 => (dy, -dx),
+            Symmetry::ReflectDiagonal => (dy, dx),
+            Symmetry::ReflectVertical => (-dx, dy),
+            Symmetry::ReflectAntiDiagonal => (-dy, -dx),
+            Symmetry::ReflectHorizontal => (dx, -dy),
+        }
+    }
+
+    pub fn is_reflection(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::ReflectDiagonal
+                | Symmetry::ReflectVertical
+                | Symmetry::ReflectAntiDiagonal
+                | Symmetry::ReflectHorizontal
+        )
+    }
+}
+
+/// Classification of a board by the transforms that map it onto itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymmetryClass {
+    /// Only the identity, the orbit has 8 boards
+    Asymmetric,
+    /// Invariant under the half turn, the orbit has 4 boards
+    HalfTurn,
+    /// Invariant under the quarter turn, the orbit has 2 boards
+    QuarterTurn,
+    /// Invariant under some reflection; never the case for a complete solution
+    Reflective,
+}
+
+impl SymmetryClass {
+    pub fn from_stabilizer(stabilizer: &[Symmetry]) -> Self {
+        if stabilizer.iter().any(|s| s.is_reflection()) {
+            SymmetryClass::Reflective
+        } else if stabilizer.contains(&Symmetry::Rotate90) {
+            SymmetryClass::QuarterTurn
+        } else if stabilizer.contains(&Symmetry::Rotate180) {
+            SymmetryClass::HalfTurn
+        } else {
+            SymmetryClass::Asymmetric
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FundamentalSolution {
+    board: Board,
+    stabilizer: Vec<Symmetry>,
+}
+
+impl FundamentalSolution {
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn get_stabilizer(&self) -> &Vec<Symmetry> {
+        &self.stabilizer
+    }
+
+    pub fn get_orbit_size(&self) -> usize {
+        Symmetry::ALL.len() / self.stabilizer.len()
+    }
+
+    pub fn get_class(&self) -> SymmetryClass {
+        SymmetryClass::from_stabilizer(&self.stabilizer)
+    }
+}
+
+/// Iterator over the solutions that are the canonical form of their orbit
+pub struct FundamentalSolutions {
+    cols: usize,
+    solutions: Solutions,
+}
+
+impl Iterator for FundamentalSolutions {
+    type Item = FundamentalSolution;
+
+    fn next(&mut self) -> Option<FundamentalSolution> {
+        for permutation in self.solutions.by_ref() {
+            let queens = permutation
+                .iter()
+                .enumerate()
+                .map(|(y, x)| (*x, y + 1))
+                .collect::<Vec<(usize, usize)>>();
+            let signature = queens_signature(self.cols, &queens, &Symmetry::Identity);
+
+            let mut canonical = true;
+            let mut stabilizer = vec![];
+            for s in Symmetry::ALL.iter() {
+                let transformed = queens_signature(self.cols, &queens, s);
+                if transformed < signature {
+                    canonical = false;
+                    break;
+                }
+                if transformed == signature {
+                    stabilizer.push(*s);
+                }
+            }
+
+            if canonical {
+                let board = Board::from_permutation(&permutation)
+                    .expect("Solutions iterator yielded an invalid permutation");
+                return Some(FundamentalSolution { board, stabilizer });
+            }
+        }
+
+        None
+    }
+}
+
+/// Enumerate each fundamental solution of a board with `cols` columns exactly once
+///
+/// # Example
+///
+/// ```
+/// use andaluz_core::symmetry::{fundamental_solutions, SymmetryClass};
+///
+/// let fundamentals = fundamental_solutions(8).unwrap().collect::<Vec<_>>();
+///
+/// assert_eq!(fundamentals.len(), 12);
+/// assert_eq!(
+///     fundamentals
+///         .iter()
+///         .filter(|f| f.get_class() == SymmetryClass::HalfTurn)
+///         .count(),
+///     1
+/// );
+/// ```
+pub fn fundamental_solutions(cols: usize) -> Result<FundamentalSolutions, String> {
+    Ok(FundamentalSolutions {
+        cols,
+        solutions: solutions(cols)?,
+    })
+}
+
+fn queens_signature(cols: usize, queens: &[(usize, usize)], symmetry: &Symmetry) -> Vec<u8> {
+    let mut signature = vec![0_u8; (cols * cols).div_ceil(8)];
+    for (x, y) in queens {
+        let (x, y) = symmetry.apply(cols, cols, x, y);
+        let (byte, mask) = BitBoard::signature_bit(x - 1 + cols * (y - 1));
+        signature[byte] |= mask;
+    }
+    signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counter::count_solutions;
+
+    fn eight_queens() -> Board {
+        Board::from_permutation(&[5, 1, 8, 4, 2, 7, 3, 6]).unwrap()
+    }
+
+    #[test]
+    fn transforms_compose() {
+        let board = eight_queens();
+
+        let turned = board
+            .rot90()
+            .unwrap()
+            .rot90()
+            .unwrap()
+            .rot90()
+            .unwrap()
+            .rot90()
+            .unwrap();
+        assert_eq!(turned.get_signature(), board.get_signature());
+
+        let half = board.rot90().unwrap().rot90().unwrap();
+        assert_eq!(
+            half.get_signature(),
+            board.rot180().unwrap().get_signature()
+        );
+
+        let reflected = board
+            .reflect_vertical()
+            .unwrap()
+            .reflect_horizontal()
+            .unwrap();
+        assert_eq!(reflected.get_signature(), half.get_signature());
+    }
+
+    #[test]
+    fn canonical_form() {
+        let mut board = eight_queens();
+        let canonical = board.get_canonical_signature().unwrap();
+
+        for s in Symmetry::ALL.iter() {
+            let mut transformed = board.transform(s).unwrap();
+            assert_eq!(transformed.get_canonical_signature().unwrap(), canonical);
+        }
+    }
+
+    #[test]
+    fn stabilizer() {
+        let board = eight_queens();
+        assert_eq!(board.get_stabilizer(), vec![Symmetry::Identity]);
+        assert_eq!(board.get_orbit_size(), 8);
+
+        let board = Board::from_permutation(&[2, 4, 1, 3]).unwrap();
+        assert_eq!(board.get_orbit_size(), 2);
+        assert_eq!(
+            SymmetryClass::from_stabilizer(&board.get_stabilizer()),
+            SymmetryClass::QuarterTurn
+        );
+
+        let board = Board::new(5);
+        assert_eq!(board.get_orbit_size(), 1);
+    }
+
+    #[test]
+    fn rectangle_group() {
+        let mut board = Board::new_rectangle(5, 3);
+        board.toggle_cell(&1, &1).unwrap();
+        board.toggle_cell(&5, &3).unwrap();
+
+        assert_eq!(board.get_symmetries().len(), 4);
+        assert_eq!(board.get_equivalent_signatures().unwrap().len(), 4);
+        assert_eq!(
+            board.get_stabilizer(),
+            vec![Symmetry::Identity, Symmetry::Rotate180]
+        );
+        assert_eq!(board.get_orbit_size(), 2);
+        assert!(board.rot90().is_err());
+    }
+
+    #[test]
+    fn fundamental_counts() {
+        for cols in 1..=10 {
+            let fundamentals = fundamental_solutions(cols).unwrap().collect::<Vec<_>>();
+            let count = count_solutions(cols).unwrap();
+
+            assert_eq!(fundamentals.len() as u64, *count.get_fundamental());
+            assert_eq!(
+                fundamentals
+                    .iter()
+                    .map(|f| f.get_orbit_size() as u64)
+                    .sum::<u64>(),
+                *count.get_total()
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::symmetry::Symmetry;
+use std::fmt;
+
+/// How the edges of a board are glued together
+///
+/// Lines of attack leaving the board through a glued edge come back through the opposite
+/// one. On the Klein bottle the top and bottom edges are glued with a twist, so the column
+/// is mirrored and the line comes back going the other way horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topology {
+    Plane,
+    /// The left and right edges are glued
+    Cylinder,
+    /// Both pairs of opposite edges are glued, as in modular n-queens
+    Torus,
+    /// The left and right edges are glued, the top and bottom edges are glued mirrored
+    KleinBottle,
+}
+
+impl Topology {
+    pub fn from_name(name: &str) -> Result<Topology, String> {
+        match name {
+            "plane" => Ok(Topology::Plane),
+            "cylinder" => Ok(Topology::Cylinder),
+            "torus" => Ok(Topology::Torus),
+            "klein" => Ok(Topology::KleinBottle),
+            _ => Err(format!("Unknown topology '{}'", name)),
+        }
+    }
+
+    /// Move from the 0-based cell `(x, y)` by `(dx, dy)`
+    ///
+    /// Returns the new cell and the direction of the move once there, which changes after
+    /// crossing the twisted edge of the Klein bottle, or `None` when the move leaves the
+    /// board.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use andaluz_core::topology::Topology;
+    ///
+    /// assert_eq!(Topology::Plane.step(5, 5, 4, 4, 1, 1), None);
+    /// assert_eq!(Topology::Torus.step(5, 5, 4, 4, 1, 1), Some((0, 0, 1, 1)));
+    /// assert_eq!(Topology::Cylinder.step(5, 5, 4, 2, 1, 1), Some((0, 3, 1, 1)));
+    /// assert_eq!(Topology::KleinBottle.step(5, 5, 1, 4, 1, 1), Some((2, 0, -1, 1)));
+    /// ```
+    pub fn step(
+        &self,
+        cols: usize,
+        rows: usize,
+        x: i64,
+        y: i64,
+        dx: i64,
+        dy: i64,
+    ) -> Option<(i64, i64, i64, i64)> {
+        let (cols, rows) = (cols as i64, rows as i64);
+        let (mut x, mut y, mut dx) = (x + dx, y + dy, dx);
+
+        match self {
+            Topology::Plane => {}
+            Topology::Cylinder => x = x.rem_euclid(cols),
+            Topology::Torus => {
+                x = x.rem_euclid(cols);
+                y = y.rem_euclid(rows);
+            }
+            Topology::KleinBottle => {
+                // Every crossing of the twisted edge mirrors the column
+                if y.div_euclid(rows) % 2 != 0 {
+                    x = cols - 1 - x;
+                    dx = -dx;
+                }
+                x = x.rem_euclid(cols);
+                y = y.rem_euclid(rows);
+            }
+        }
+
+        if x < 0 || y < 0 || x >= cols || y >= rows {
+            None
+        } else {
+            Some((x, y, dx, dy))
+        }
+    }
+
+    /// Transforms of a board with the given dimensions that keep the gluing of the edges
+    pub fn group(&self, cols: usize, rows: usize) -> &'static [Symmetry] {
+        match self {
+            Topology::Plane | Topology::Torus => Symmetry::group(cols, rows),
+            Topology::Cylinder | Topology::KleinBottle => &Symmetry::RECTANGLE,
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn klein_bottle_round_trip() {
+        // Going twice across the twisted edge restores the column and the direction
+        let (mut x, mut y, mut dx, mut dy) = (1, 0, 1, 1);
+        for _ in 0..10 {
+            let next = Topology::KleinBottle.step(5, 5, x, y, dx, dy).unwrap();
+            x = next.0;
+            y = next.1;
+            dx = next.2;
+            dy = next.3;
+        }
+        assert_eq!((x, y, dx, dy), (1, 0, 1, 1));
+    }
+}